@@ -12,12 +12,14 @@ pub fn dev() -> CorsLayer {
             Method::GET,
             Method::POST,
             Method::PUT,
+            Method::PATCH,
             Method::DELETE,
         ])
         .allow_headers([
             header::CONTENT_TYPE,
             header::AUTHORIZATION,
         ])
+        .expose_headers([header::RETRY_AFTER])
         .allow_credentials(true)
 }
 
@@ -36,11 +38,13 @@ pub fn prod() -> CorsLayer {
             Method::GET,
             Method::POST,
             Method::PUT,
+            Method::PATCH,
             Method::DELETE,
         ])
         .allow_headers([
             header::CONTENT_TYPE,
             header::AUTHORIZATION,
         ])
+        .expose_headers([header::RETRY_AFTER])
         .allow_credentials(true)
 }
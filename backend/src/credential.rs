@@ -0,0 +1,79 @@
+use axum::{
+    extract::{FromRef, FromRequestParts, Json},
+    http::{header, request::Parts, Method, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use tower_cookies::Cookies;
+
+use crate::data::{Database, API_TOKEN_PREFIX};
+use crate::types::ApiTokenScope;
+
+#[derive(Serialize)]
+pub enum CredentialStatus {
+    InsufficientScope,
+    InternalServerError,
+}
+
+/// What a request is authenticated with: an API token from `Authorization: Bearer`,
+/// or else the `session_uuid` cookie.
+///
+/// Handlers pass it on to the data layer, which accepts either. Requests made with a
+/// read-only API token are turned away here unless they are `GET` or `HEAD`. The data layer
+/// checks the scope again where a `GET` can write, like creating a missing invitation code.
+/// Routes that only a logged in user may use, like managing sessions or API tokens,
+/// read the cookie themselves instead.
+pub struct Credential(pub Option<String>);
+
+impl<S> FromRequestParts<S> for Credential
+where
+    S: Send + Sync,
+    Database: FromRef<S>,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let bearer = parts
+            .headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(|token| token.trim().to_string());
+
+        if let Some(token) = bearer {
+            // Anything else would be a session UUID, which belongs in the cookie
+            if !token.starts_with(API_TOKEN_PREFIX) {
+                return Ok(Self(None));
+            }
+
+            let read_only = match Database::from_ref(state).api_token_scope(token.clone()).await {
+                Ok(scope) => scope == Some(ApiTokenScope::Read),
+                Err(e) => {
+                    eprintln!("API token scope error: {e}");
+                    return Err(
+                        (StatusCode::INTERNAL_SERVER_ERROR, Json(CredentialStatus::InternalServerError)).into_response(),
+                    );
+                }
+            };
+
+            if read_only && parts.method != Method::GET && parts.method != Method::HEAD {
+                return Err((StatusCode::FORBIDDEN, Json(CredentialStatus::InsufficientScope)).into_response());
+            }
+
+            // Unknown and expired tokens are passed on, the data layer treats them as not logged in
+            return Ok(Self(Some(token)));
+        }
+
+        let cookies = Cookies::from_request_parts(parts, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+
+        // An API token smuggled into the cookie would skip the scope check above
+        Ok(Self(
+            cookies
+                .get("session_uuid")
+                .map(|cookie| cookie.value().to_string())
+                .filter(|value| !value.starts_with(API_TOKEN_PREFIX)),
+        ))
+    }
+}
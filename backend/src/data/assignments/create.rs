@@ -0,0 +1,85 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::params;
+
+use crate::types::NewAssignment;
+use super::super::Database;
+use super::super::session;
+use super::super::permissions::{self, Permission};
+
+pub enum CreateAssignmentOutcome {
+    Success(i32),
+    NotLoggedIn,
+    NotPermitted,
+    RoomArchived,
+    InvalidAssignment,
+}
+
+impl Database {
+    /// Attempts to create a new assignment in a room using the logged in user's ID and a given room ID.
+    ///
+    /// Only owners and teachers of the room may create assignments, and not in archived rooms.
+    /// The title must not be empty and the maximum points must be positive. On success the ID
+    /// of the new assignment is returned.
+    pub async fn create_assignment(
+        &self,
+        session_uuid: String,
+        room_id: i32,
+        assignment: NewAssignment,
+    ) -> Result<CreateAssignmentOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| session::user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(CreateAssignmentOutcome::NotLoggedIn);
+        };
+
+        let is_permitted: bool = conn
+            .interact(move |conn| permissions::has_permission(conn, room_id, user_id, Permission::ManageAssignments))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        if !is_permitted {
+            return Ok(CreateAssignmentOutcome::NotPermitted);
+        }
+
+        let archived: bool = conn
+            .interact(move |conn| permissions::is_archived(conn, room_id))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        if archived {
+            return Ok(CreateAssignmentOutcome::RoomArchived);
+        }
+
+        let title = assignment.title.trim().to_string();
+
+        if title.is_empty() || assignment.max_points <= 0 {
+            return Ok(CreateAssignmentOutcome::InvalidAssignment);
+        }
+
+        let assignment_id = conn
+            .interact(move |conn| -> Result<i32> {
+                conn.execute(
+                    "INSERT INTO assignments (room_id, title, instructions, due_date, max_points)
+                    VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![
+                        room_id,
+                        title,
+                        assignment.instructions,
+                        assignment.due_date,
+                        assignment.max_points,
+                    ],
+                )?;
+
+                Ok(i32::try_from(conn.last_insert_rowid())?)
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        Ok(CreateAssignmentOutcome::Success(assignment_id))
+    }
+}
@@ -0,0 +1,71 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::{OptionalExtension, params};
+
+use crate::types::Assignment;
+use super::super::Database;
+use super::super::session;
+use super::super::permissions;
+
+pub enum GetAssignmentOutcome {
+    Success(Assignment),
+    NotLoggedIn,
+    NotFound,
+}
+
+impl Database {
+    /// Attempts to get a single assignment using the logged in user's ID and a given assignment ID.
+    ///
+    /// Assignments in rooms the user is not a member of are reported as
+    /// [`GetAssignmentOutcome::NotFound`] so their existence is not revealed.
+    pub async fn get_assignment(
+        &self,
+        session_uuid: String,
+        assignment_id: i32,
+    ) -> Result<GetAssignmentOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| session::user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(GetAssignmentOutcome::NotLoggedIn);
+        };
+
+        let assignment: Option<Assignment> = conn
+            .interact(move |conn| {
+                conn.query_row(
+                    &format!(
+                        "
+                        SELECT a.id, a.room_id, a.title, a.instructions, a.due_date, a.max_points
+                        FROM assignments a
+                        JOIN room_members rm ON rm.room_id = a.room_id
+                        JOIN rooms r ON r.id = a.room_id
+                        WHERE a.id = ?1 AND rm.user_id = ?2 AND r.deleted_at IS NULL AND {}
+                        ",
+                        permissions::NOT_HIDDEN,
+                    ),
+                    params![assignment_id, user_id],
+                    |row| {
+                        Ok(Assignment {
+                            id: row.get(0)?,
+                            room_id: row.get(1)?,
+                            title: row.get(2)?,
+                            instructions: row.get(3)?,
+                            due_date: row.get(4)?,
+                            max_points: row.get(5)?,
+                        })
+                    },
+                )
+                .optional()
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        match assignment {
+            Some(assignment) => Ok(GetAssignmentOutcome::Success(assignment)),
+            None => Ok(GetAssignmentOutcome::NotFound),
+        }
+    }
+}
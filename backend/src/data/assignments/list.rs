@@ -0,0 +1,94 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::params;
+
+use crate::types::Assignment;
+use super::super::Database;
+use super::super::session;
+use super::super::permissions;
+
+pub enum ListAssignmentsOutcome {
+    Success(Vec<Assignment>),
+    NotLoggedIn,
+    NotMember,
+}
+
+impl Database {
+    /// Attempts to list the assignments of a room using the logged in user's ID and a given room ID.
+    ///
+    /// Only members of the room (including its owner) may list its assignments.
+    pub async fn list_assignments(
+        &self,
+        session_uuid: String,
+        room_id: i32,
+    ) -> Result<ListAssignmentsOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| session::user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(ListAssignmentsOutcome::NotLoggedIn);
+        };
+
+        let is_member: bool = conn
+            .interact(move |conn| {
+                conn.query_row(
+                    &format!(
+                        "
+                        SELECT EXISTS(
+                            SELECT 1
+                            FROM room_members rm
+                            JOIN rooms r ON r.id = rm.room_id
+                            WHERE rm.room_id = ?1 AND rm.user_id = ?2 AND r.deleted_at IS NULL AND {}
+                        )
+                        ",
+                        permissions::NOT_HIDDEN,
+                    ),
+                    params![room_id, user_id],
+                    |row| row.get(0),
+                )
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        if !is_member {
+            return Ok(ListAssignmentsOutcome::NotMember);
+        }
+
+        let assignments = conn
+            .interact(move |conn| -> Result<Vec<Assignment>> {
+                let mut stmt = conn.prepare(
+                    "
+                    SELECT id, room_id, title, instructions, due_date, max_points
+                    FROM assignments
+                    WHERE room_id = ?1
+                    ORDER BY due_date IS NULL, due_date, id
+                    ",
+                )?;
+
+                let assignments_iter = stmt.query_map(params![room_id], |row| {
+                    Ok(Assignment {
+                        id: row.get(0)?,
+                        room_id: row.get(1)?,
+                        title: row.get(2)?,
+                        instructions: row.get(3)?,
+                        due_date: row.get(4)?,
+                        max_points: row.get(5)?,
+                    })
+                })?;
+
+                let mut assignments = Vec::new();
+                for assignment in assignments_iter {
+                    assignments.push(assignment?);
+                }
+
+                Ok(assignments)
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        Ok(ListAssignmentsOutcome::Success(assignments))
+    }
+}
@@ -0,0 +1,8 @@
+mod create;
+pub use create::CreateAssignmentOutcome;
+
+mod get;
+pub use get::GetAssignmentOutcome;
+
+mod list;
+pub use list::ListAssignmentsOutcome;
@@ -1,6 +1,9 @@
 use anyhow::{Result, anyhow};
-use deadpool_sqlite::{Config, Pool, Runtime};
-use deadpool_sqlite::rusqlite::{self, params};
+use deadpool_sqlite::{Config, Hook, HookError, Pool, Runtime};
+use deadpool_sqlite::rusqlite::params;
+
+use super::migrations;
+use super::utils::now;
 
 #[derive(Clone)]
 pub struct Database {
@@ -8,10 +11,11 @@ pub struct Database {
 }
 
 impl Database {
-    /// Creates a new instance and initializes the database.
+    /// Creates a new instance and brings the database schema up to date.
     ///
-    /// This sets up the required database schema if it does not already exist,
-    /// ensures a default admin user is present, and recreates the `sessions` table.
+    /// Every connection in the pool enforces foreign keys, which SQLite leaves off by default.
+    /// Pending migrations are applied first, up to [`latest_schema_version`](super::latest_schema_version),
+    /// then a default admin user is ensured.
     ///
     /// # Errors
     ///
@@ -19,77 +23,44 @@ impl Database {
     /// - the database configuration or connection pool cannot be created
     /// - a database connection cannot be acquired from the pool
     /// - a database task fails to run or complete
-    /// - executing any of the schema initialization or setup SQL statements fails
+    /// - the database has a newer schema than this binary knows
+    /// - a migration or the admin user setup fails
     pub async fn new() -> Result<Self> {
         let cfg = Config::new("db.sqlite3");
-        let pool = cfg.create_pool(Runtime::Tokio1)?;
+        let pool = cfg
+            .builder(Runtime::Tokio1)?
+            .post_create(Hook::async_fn(|conn, _| {
+                Box::pin(async move {
+                    conn.interact(|conn| conn.pragma_update(None, "foreign_keys", true))
+                        .await
+                        .map_err(|e| HookError::message(e.to_string()))?
+                        .map_err(HookError::Backend)
+                })
+            }))
+            .build()?;
 
         {
             let conn = pool.get().await?;
-            conn.interact(|conn| {
-                conn.execute(
-                    "CREATE TABLE IF NOT EXISTS users (
-                        id INTEGER PRIMARY KEY,
-                        name TEXT NOT NULL,
-                        surname TEXT NOT NULL,
-                        password_hash TEXT NOT NULL,
-                        email TEXT NOT NULL
-                    )",
-                    [],
-                )?;
+            let applied = conn
+                .interact(migrations::migrate)
+                .await
+                .map_err(|e| anyhow!("{e}"))??;
+
+            for description in applied {
+                println!("Applied migration: {description}");
+            }
 
+            conn.interact(|conn| {
                 conn.execute(
-                    "INSERT INTO users (id, name, surname, password_hash, email)
-                    VALUES (1, ?1, ?2, ?3, ?4)
+                    "INSERT INTO users (id, name, surname, password_hash, email, email_verified_at)
+                    VALUES (1, ?1, ?2, ?3, ?4, ?5)
                     ON CONFLICT(id) DO UPDATE SET
                         name = excluded.name,
                         surname = excluded.surname,
                         password_hash = excluded.password_hash,
                         email = excluded.email",
-                        params!["Admin", "Admin", "passwd_hash", "admin@example.com"],
-                )?;
-                
-                conn.execute("DROP TABLE IF EXISTS sessions", [])?;
-                conn.execute(
-                    "CREATE TABLE sessions (
-                        uuid TEXT PRIMARY KEY,
-                        user_id INTEGER NOT NULL
-                    )",
-                    [],
-                )?;
-
-                conn.execute(
-                    "CREATE TABLE IF NOT EXISTS rooms (
-                        id INTEGER PRIMARY KEY,
-                        owner INTEGER NOT NULL,
-                        name TEXT NOT NULL,
-                        description TEXT
-                    )",
-                    [],
-                )?;
-
-                // To link users to rooms
-                conn.execute(
-                    "CREATE TABLE IF NOT EXISTS room_members (
-                        room_id INTEGER NOT NULL,
-                        user_id INTEGER NOT NULL,
-                        PRIMARY KEY (room_id, user_id)
-                    )",
-                    [],
-                )?;
-
-                // Reset codes every server restart
-                conn.execute("DROP TABLE IF EXISTS invitation_codes", [])?;
-                conn.execute(
-                    "CREATE TABLE invitation_codes (
-                        room_id INTEGER NOT NULL,
-                        code STRING NOT NULL,
-                        PRIMARY KEY (room_id, code)
-                    )",
-                    [],
-                )?;
-
-                Ok::<_, rusqlite::Error>(())
+                        params!["Admin", "Admin", "passwd_hash", "admin@example.com", now()],
+                )
             })
                 .await
                 .map_err(|e| anyhow!("{e}"))??;
@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::params;
+use tokio::sync::mpsc;
+
+use crate::types::{Assignment, Gradebook, GradebookCell, GradebookRow};
+use super::super::Database;
+use super::super::session;
+use super::super::permissions::{self, Permission};
+
+/// Number of rows buffered between the database task and the response body.
+const ROW_BUFFER: usize = 32;
+
+pub enum ExportGradebookOutcome {
+    Success(Gradebook),
+    NotLoggedIn,
+    NotPermitted,
+}
+
+impl Database {
+    /// Attempts to export the gradebook of a room using the logged in user's ID and a given room ID.
+    ///
+    /// Only owners and teachers may export it. The gradebook has one row per student and one
+    /// cell per assignment. Rows are sent over a channel while the query runs,
+    /// so the whole gradebook never has to be held in memory. A cell is flagged late when the
+    /// submission was made after the assignment's due date.
+    pub async fn export_gradebook(
+        &self,
+        session_uuid: String,
+        room_id: i32,
+    ) -> Result<ExportGradebookOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| session::user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(ExportGradebookOutcome::NotLoggedIn);
+        };
+
+        let is_permitted: bool = conn
+            .interact(move |conn| permissions::has_permission(conn, room_id, user_id, Permission::ExportGradebook))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        if !is_permitted {
+            return Ok(ExportGradebookOutcome::NotPermitted);
+        }
+
+        let assignments = conn
+            .interact(move |conn| -> Result<Vec<Assignment>> {
+                let mut stmt = conn.prepare(
+                    "
+                    SELECT id, room_id, title, instructions, due_date, max_points
+                    FROM assignments
+                    WHERE room_id = ?1
+                    ORDER BY due_date IS NULL, due_date, id
+                    ",
+                )?;
+
+                let assignments_iter = stmt.query_map(params![room_id], |row| {
+                    Ok(Assignment {
+                        id: row.get(0)?,
+                        room_id: row.get(1)?,
+                        title: row.get(2)?,
+                        instructions: row.get(3)?,
+                        due_date: row.get(4)?,
+                        max_points: row.get(5)?,
+                    })
+                })?;
+
+                let mut assignments = Vec::new();
+                for assignment in assignments_iter {
+                    assignments.push(assignment?);
+                }
+
+                Ok(assignments)
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let columns: HashMap<i32, usize> = assignments
+            .iter()
+            .enumerate()
+            .map(|(i, assignment)| (assignment.id, i))
+            .collect();
+        let assignment_ids: Vec<i32> = assignments.iter().map(|a| a.id).collect();
+
+        let (tx, rows) = mpsc::channel(ROW_BUFFER);
+
+        // The connection moves into the task and goes back to the pool once every row was sent
+        tokio::spawn(async move {
+            let error_tx = tx.clone();
+
+            let result = conn
+                .interact(move |conn| -> Result<()> {
+                    let mut stmt = conn.prepare(
+                        "
+                        SELECT u.id, u.name, u.surname, u.email, s.assignment_id, g.points,
+                            s.submitted_at > a.due_date
+                        FROM room_members rm
+                        JOIN users u ON u.id = rm.user_id
+                        LEFT JOIN submissions s ON s.user_id = u.id
+                            AND s.assignment_id IN (SELECT id FROM assignments WHERE room_id = ?1)
+                        LEFT JOIN assignments a ON a.id = s.assignment_id
+                        LEFT JOIN grades g ON g.submission_id = s.id
+                        WHERE rm.room_id = ?1 AND rm.role = 'student'
+                        ORDER BY u.surname, u.name, u.id
+                        ",
+                    )?;
+
+                    let mut query = stmt.query(params![room_id])?;
+                    let mut current: Option<GradebookRow> = None;
+
+                    while let Some(row) = query.next()? {
+                        let member_id: i32 = row.get(0)?;
+
+                        if current.as_ref().is_none_or(|c| c.user_id != member_id) {
+                            if let Some(finished) = current.take()
+                                && tx.blocking_send(Ok(finished)).is_err()
+                            {
+                                // Receiver is gone, e.g. the client disconnected
+                                return Ok(());
+                            }
+
+                            current = Some(GradebookRow {
+                                user_id: member_id,
+                                name: row.get(1)?,
+                                surname: row.get(2)?,
+                                email: row.get(3)?,
+                                grades: assignment_ids
+                                    .iter()
+                                    .map(|&assignment_id| GradebookCell {
+                                        assignment_id,
+                                        points: None,
+                                        submitted: false,
+                                        late: false,
+                                    })
+                                    .collect(),
+                                total: 0.0,
+                            });
+                        }
+
+                        let Some(assignment_id) = row.get::<_, Option<i32>>(4)? else {
+                            continue;
+                        };
+                        let (Some(member), Some(&column)) = (current.as_mut(), columns.get(&assignment_id)) else {
+                            continue;
+                        };
+
+                        let points: Option<f64> = row.get(5)?;
+                        let cell = &mut member.grades[column];
+                        cell.points = points;
+                        cell.submitted = true;
+                        cell.late = row.get::<_, Option<bool>>(6)?.unwrap_or(false);
+                        member.total += points.unwrap_or(0.0);
+                    }
+
+                    if let Some(finished) = current {
+                        let _ = tx.blocking_send(Ok(finished));
+                    }
+
+                    Ok(())
+                })
+                .await
+                .map_err(|e| anyhow!("{e}"))
+                .and_then(|result| result);
+
+            if let Err(e) = result {
+                let _ = error_tx.send(Err(e)).await;
+            }
+        });
+
+        Ok(ExportGradebookOutcome::Success(Gradebook { assignments, rows }))
+    }
+}
@@ -0,0 +1,94 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::{OptionalExtension, params};
+
+use crate::types::{Grade, GradeState};
+use super::super::Database;
+use super::super::session;
+use super::super::permissions::{self, Permission};
+
+pub enum GetGradeOutcome {
+    Success(Grade),
+    NotLoggedIn,
+    NotFound,
+}
+
+impl Database {
+    /// Attempts to get the grade of a submission using the logged in user's ID and a given submission ID.
+    ///
+    /// Owners and teachers of the room always see the grade, the submitter only once it has been returned.
+    /// In every other case [`GetGradeOutcome::NotFound`] is returned.
+    pub async fn get_grade(
+        &self,
+        session_uuid: String,
+        submission_id: i32,
+    ) -> Result<GetGradeOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| session::user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(GetGradeOutcome::NotLoggedIn);
+        };
+
+        let grade: Option<Grade> = conn
+            .interact(move |conn| -> Result<Option<Grade>> {
+                let grade: Option<(i32, i64, Grade)> = conn
+                    .query_row(
+                        "
+                        SELECT a.room_id, s.user_id,
+                            g.submission_id, a.id, a.title, g.points, a.max_points, g.feedback, g.state, g.graded_at
+                        FROM grades g
+                        JOIN submissions s ON s.id = g.submission_id
+                        JOIN assignments a ON a.id = s.assignment_id
+                        JOIN rooms r ON r.id = a.room_id
+                        WHERE g.submission_id = ?1 AND r.deleted_at IS NULL
+                        ",
+                        params![submission_id],
+                        |row| {
+                            Ok((
+                                row.get(0)?,
+                                row.get(1)?,
+                                Grade {
+                                    submission_id: row.get(2)?,
+                                    assignment_id: row.get(3)?,
+                                    assignment_title: row.get(4)?,
+                                    points: row.get(5)?,
+                                    max_points: row.get(6)?,
+                                    feedback: row.get(7)?,
+                                    state: row.get(8)?,
+                                    graded_at: row.get(9)?,
+                                },
+                            ))
+                        },
+                    )
+                    .optional()?;
+
+                let Some((room_id, submitter_id, grade)) = grade else {
+                    return Ok(None);
+                };
+
+                if permissions::is_hidden_from(conn, room_id, user_id)? {
+                    return Ok(None);
+                }
+
+                let is_returned_to_user = submitter_id == user_id && grade.state == GradeState::Returned;
+                if !is_returned_to_user
+                    && !permissions::has_permission(conn, room_id, user_id, Permission::GradeSubmissions)?
+                {
+                    return Ok(None);
+                }
+
+                Ok(Some(grade))
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        match grade {
+            Some(grade) => Ok(GetGradeOutcome::Success(grade)),
+            None => Ok(GetGradeOutcome::NotFound),
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::{OptionalExtension, params};
+
+use crate::types::NewGrade;
+use super::super::Database;
+use super::super::session;
+use super::super::permissions::{self, Permission};
+use super::super::utils;
+
+pub enum GradeSubmissionOutcome {
+    Success,
+    NotLoggedIn,
+    NotPermitted,
+    SubmissionNotFound,
+    InvalidPoints,
+    RoomArchived,
+}
+
+impl Database {
+    /// Attempts to grade a submission using the logged in user's ID and a given submission ID.
+    ///
+    /// Only owners and teachers of the room the submission belongs to may grade it. Grading an already
+    /// graded submission replaces the previous grade. The points must lie between zero and the
+    /// assignment's maximum points. Grades in archived rooms cannot change.
+    pub async fn grade_submission(
+        &self,
+        session_uuid: String,
+        submission_id: i32,
+        grade: NewGrade,
+    ) -> Result<GradeSubmissionOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| session::user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(GradeSubmissionOutcome::NotLoggedIn);
+        };
+
+        let assignment: Option<(i32, i32)> = conn
+            .interact(move |conn| {
+                conn.query_row(
+                    "
+                    SELECT a.room_id, a.max_points
+                    FROM submissions s
+                    JOIN assignments a ON a.id = s.assignment_id
+                    WHERE s.id = ?1
+                    ",
+                    params![submission_id],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some((room_id, max_points)) = assignment else {
+            return Ok(GradeSubmissionOutcome::SubmissionNotFound);
+        };
+
+        let is_permitted: bool = conn
+            .interact(move |conn| permissions::has_permission(conn, room_id, user_id, Permission::GradeSubmissions))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        if !is_permitted {
+            return Ok(GradeSubmissionOutcome::NotPermitted);
+        }
+
+        let archived: bool = conn
+            .interact(move |conn| permissions::is_archived(conn, room_id))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        if archived {
+            return Ok(GradeSubmissionOutcome::RoomArchived);
+        }
+
+        if !(0.0..=f64::from(max_points)).contains(&grade.points) {
+            return Ok(GradeSubmissionOutcome::InvalidPoints);
+        }
+
+        conn.interact(move |conn| {
+            conn.execute(
+                "INSERT INTO grades (submission_id, points, feedback, state, graded_by, graded_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                ON CONFLICT(submission_id) DO UPDATE SET
+                    points = excluded.points,
+                    feedback = excluded.feedback,
+                    state = excluded.state,
+                    graded_by = excluded.graded_by,
+                    graded_at = excluded.graded_at",
+                params![submission_id, grade.points, grade.feedback, grade.state, user_id, utils::now()],
+            )
+        })
+        .await
+        .map_err(|e| anyhow!("{e}"))??;
+
+        Ok(GradeSubmissionOutcome::Success)
+    }
+}
@@ -0,0 +1,72 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::params;
+
+use crate::types::Grade;
+use super::super::Database;
+use super::super::session;
+use super::super::permissions;
+
+pub enum GetMyGradesOutcome {
+    Success(Vec<Grade>),
+    NotLoggedIn,
+}
+
+impl Database {
+    /// Attempts to get all returned grades of the logged in user's own submissions.
+    ///
+    /// Grades that have not been returned yet are not included.
+    pub async fn get_my_grades(&self, session_uuid: String) -> Result<GetMyGradesOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| session::user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(GetMyGradesOutcome::NotLoggedIn);
+        };
+
+        let grades = conn
+            .interact(move |conn| -> Result<Vec<Grade>> {
+                // Grades of rooms the user has left stay visible, unless the room is hidden
+                let mut stmt = conn.prepare(&format!(
+                    "
+                    SELECT g.submission_id, a.id, a.title, g.points, a.max_points, g.feedback, g.state, g.graded_at
+                    FROM grades g
+                    JOIN submissions s ON s.id = g.submission_id
+                    JOIN assignments a ON a.id = s.assignment_id
+                    JOIN rooms r ON r.id = a.room_id
+                    LEFT JOIN room_members rm ON rm.room_id = r.id AND rm.user_id = s.user_id
+                    WHERE s.user_id = ?1 AND g.state = 'returned' AND r.deleted_at IS NULL AND {}
+                    ORDER BY g.graded_at DESC
+                    ",
+                    permissions::NOT_HIDDEN,
+                ))?;
+
+                let grades_iter = stmt.query_map(params![user_id], |row| {
+                    Ok(Grade {
+                        submission_id: row.get(0)?,
+                        assignment_id: row.get(1)?,
+                        assignment_title: row.get(2)?,
+                        points: row.get(3)?,
+                        max_points: row.get(4)?,
+                        feedback: row.get(5)?,
+                        state: row.get(6)?,
+                        graded_at: row.get(7)?,
+                    })
+                })?;
+
+                let mut grades = Vec::new();
+                for grade in grades_iter {
+                    grades.push(grade?);
+                }
+
+                Ok(grades)
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        Ok(GetMyGradesOutcome::Success(grades))
+    }
+}
@@ -0,0 +1,11 @@
+mod export;
+pub use export::ExportGradebookOutcome;
+
+mod get;
+pub use get::GetGradeOutcome;
+
+mod grade;
+pub use grade::GradeSubmissionOutcome;
+
+mod mine;
+pub use mine::GetMyGradesOutcome;
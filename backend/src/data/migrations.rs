@@ -0,0 +1,658 @@
+use anyhow::{bail, Result};
+use deadpool_sqlite::rusqlite::{self, Connection, OptionalExtension, Transaction, TransactionBehavior, params};
+
+use super::utils::now;
+
+struct Migration {
+    description: &'static str,
+    up: fn(&Transaction) -> rusqlite::Result<()>,
+}
+
+/// Every schema change, in order, a database at `PRAGMA user_version` N has had the first N applied.
+///
+/// New migrations go at the end. One that has been released must never change, since databases
+/// that already ran it would not pick up the change.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        description: "initial schema",
+        up: initial_schema,
+    },
+    Migration {
+        description: "foreign keys",
+        up: foreign_keys,
+    },
+    Migration {
+        description: "room trash",
+        up: room_trash,
+    },
+    Migration {
+        description: "room archiving",
+        up: room_archiving,
+    },
+    Migration {
+        description: "room settings",
+        up: room_settings,
+    },
+    Migration {
+        description: "login failures by email",
+        up: login_failures_by_email,
+    },
+    Migration {
+        description: "join request codes",
+        up: join_request_codes,
+    },
+    Migration {
+        description: "normalized emails",
+        up: normalized_emails,
+    },
+];
+
+/// The schema version this binary migrates databases to.
+pub fn latest_schema_version() -> i64 {
+    i64::try_from(MIGRATIONS.len()).unwrap_or(i64::MAX)
+}
+
+/// Applies every migration the database has not had yet, each in its own transaction.
+///
+/// Foreign keys are not enforced while migrating, so tables can be rebuilt in any order,
+/// but each migration is checked against them before it is committed.
+///
+/// Returns the descriptions of the migrations that were applied.
+///
+/// # Errors
+///
+/// Returns an error if the database has a newer schema than this binary knows, since running
+/// against it could corrupt data, or if a migration fails, which leaves the database at the
+/// last migration that succeeded.
+pub fn migrate(conn: &mut Connection) -> Result<Vec<&'static str>> {
+    // Has no effect inside a transaction, so it is switched around all of them
+    conn.pragma_update(None, "foreign_keys", false)?;
+    let applied = apply_pending(conn);
+    conn.pragma_update(None, "foreign_keys", true)?;
+
+    applied
+}
+
+fn apply_pending(conn: &mut Connection) -> Result<Vec<&'static str>> {
+    let mut applied = Vec::new();
+
+    loop {
+        // Immediate, so two servers starting at once do not both apply a migration
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        let version: i64 = tx.pragma_query_value(None, "user_version", |row| row.get(0))?;
+
+        if version > latest_schema_version() {
+            bail!(
+                "database schema version {version} is newer than the {} this binary knows, refusing to start",
+                latest_schema_version()
+            );
+        }
+
+        let Some(migration) = usize::try_from(version).ok().and_then(|version| MIGRATIONS.get(version)) else {
+            return Ok(applied);
+        };
+
+        (migration.up)(&tx)?;
+
+        let violation: Option<String> = tx
+            .query_row("SELECT \"table\" FROM pragma_foreign_key_check", [], |row| row.get(0))
+            .optional()?;
+        if let Some(table) = violation {
+            bail!("migration \"{}\" left rows in {table} that break a foreign key", migration.description);
+        }
+
+        tx.pragma_update(None, "user_version", version + 1)?;
+        tx.commit()?;
+
+        applied.push(migration.description);
+    }
+}
+
+fn has_column(tx: &Transaction, table: &str, column: &str) -> rusqlite::Result<bool> {
+    tx.query_row(
+        "SELECT COUNT(*) > 0 FROM pragma_table_info(?1) WHERE name = ?2",
+        params![table, column],
+        |row| row.get(0),
+    )
+}
+
+/// Recreates `table` from `create`, which has to name it `new_<table>`, and copies its rows over.
+///
+/// SQLite cannot add constraints to an existing table, so this is how they are added.
+fn rebuild(tx: &Transaction, table: &str, create: &str) -> rusqlite::Result<()> {
+    let columns: Vec<String> = tx
+        .prepare("SELECT name FROM pragma_table_info(?1)")?
+        .query_map(params![table], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    let columns = columns.join(", ");
+
+    tx.execute(create, [])?;
+    tx.execute(&format!("INSERT INTO new_{table} ({columns}) SELECT {columns} FROM {table}"), [])?;
+    tx.execute(&format!("DROP TABLE {table}"), [])?;
+    tx.execute(&format!("ALTER TABLE new_{table} RENAME TO {table}"), [])?;
+
+    Ok(())
+}
+
+/// Creates every table, and brings databases from before migrations existed to the same shape.
+fn initial_schema(tx: &Transaction) -> rusqlite::Result<()> {
+    // Older databases are upgraded in place first, whatever already matches is left alone
+    let has_users: bool = tx.query_row(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'users'",
+        [],
+        |row| row.get(0),
+    )?;
+
+    if has_users {
+        // Users from before email verification existed count as verified
+        if !has_column(tx, "users", "email_verified_at")? {
+            tx.execute("ALTER TABLE users ADD COLUMN email_verified_at INTEGER", [])?;
+            tx.execute("UPDATE users SET email_verified_at = ?1", params![now()])?;
+        }
+
+        // Sessions used to be keyed by their UUID without any client details,
+        // such a table is rebuilt and its users have to log in again
+        if !has_column(tx, "sessions", "user_agent")? {
+            tx.execute("DROP TABLE IF EXISTS sessions", [])?;
+        }
+
+        // Rooms used to name their owner, who is now a member with the 'owner' role
+        if !has_column(tx, "room_members", "role")? {
+            tx.execute("ALTER TABLE room_members ADD COLUMN role TEXT NOT NULL DEFAULT 'student'", [])?;
+        }
+        if has_column(tx, "rooms", "owner")? {
+            tx.execute(
+                "INSERT INTO room_members (room_id, user_id, role)
+                SELECT id, owner, 'owner' FROM rooms WHERE true
+                ON CONFLICT(room_id, user_id) DO UPDATE SET role = 'owner'",
+                [],
+            )?;
+            tx.execute("ALTER TABLE rooms DROP COLUMN owner", [])?;
+        }
+
+        if !has_column(tx, "rooms", "join_approval_required")? {
+            tx.execute(
+                "ALTER TABLE rooms ADD COLUMN join_approval_required INTEGER NOT NULL DEFAULT 0",
+                [],
+            )?;
+        }
+
+        // Invitation codes used to be thrown away on every restart anyway
+        if !has_column(tx, "invitation_codes", "created_at")? {
+            tx.execute("DROP TABLE IF EXISTS invitation_codes", [])?;
+        }
+    }
+
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS users (
+            id INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            surname TEXT NOT NULL,
+            password_hash TEXT NOT NULL,
+            email TEXT NOT NULL,
+            email_verified_at INTEGER
+        )",
+        [],
+    )?;
+
+    // Sessions slide `expires_at` forward whenever they are used,
+    // `id` lets users refer to a session without knowing its secret UUID
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS sessions (
+            id INTEGER PRIMARY KEY,
+            uuid TEXT NOT NULL UNIQUE,
+            user_id INTEGER NOT NULL,
+            created_at INTEGER NOT NULL,
+            last_seen_at INTEGER NOT NULL,
+            expires_at INTEGER NOT NULL,
+            user_agent TEXT,
+            ip TEXT
+        )",
+        [],
+    )?;
+
+    // Only a hash of each token is stored, `used_at` makes them single use
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS password_reset_tokens (
+            token_hash TEXT PRIMARY KEY,
+            user_id INTEGER NOT NULL,
+            created_at INTEGER NOT NULL,
+            expires_at INTEGER NOT NULL,
+            used_at INTEGER
+        )",
+        [],
+    )?;
+
+    // Tokens users create for scripts, only a hash of each token is stored
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS api_tokens (
+            id INTEGER PRIMARY KEY,
+            user_id INTEGER NOT NULL,
+            name TEXT NOT NULL,
+            token_hash TEXT NOT NULL UNIQUE,
+            scope TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            last_used_at INTEGER,
+            expires_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+
+    // Logins waiting for a second factor, only a hash of the challenge is stored
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS login_challenges (
+            token_hash TEXT PRIMARY KEY,
+            user_id INTEGER NOT NULL,
+            expires_at INTEGER NOT NULL,
+            attempts INTEGER NOT NULL DEFAULT 0
+        )",
+        [],
+    )?;
+
+    // Single sign-on logins waiting for the user to come back from the identity provider
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS oidc_logins (
+            state_hash TEXT PRIMARY KEY,
+            pkce_verifier TEXT NOT NULL,
+            nonce TEXT NOT NULL,
+            expires_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+
+    // Accounts at the identity provider linked to users
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS oidc_identities (
+            issuer TEXT NOT NULL,
+            subject TEXT NOT NULL,
+            user_id INTEGER NOT NULL,
+            created_at INTEGER NOT NULL,
+            PRIMARY KEY (issuer, subject)
+        )",
+        [],
+    )?;
+
+    // Wrong passwords in a row per user, `locked_until` is set once there are too many
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS login_failures (
+            user_id INTEGER PRIMARY KEY,
+            failures INTEGER NOT NULL,
+            locked_until INTEGER
+        )",
+        [],
+    )?;
+
+    // `enabled_at` stays NULL until the user confirms a code during enrollment,
+    // `last_used_step` keeps a code from being accepted twice
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS totp_credentials (
+            user_id INTEGER PRIMARY KEY,
+            secret TEXT NOT NULL,
+            enabled_at INTEGER,
+            last_used_step INTEGER NOT NULL DEFAULT 0
+        )",
+        [],
+    )?;
+
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS recovery_codes (
+            user_id INTEGER NOT NULL,
+            code_hash TEXT NOT NULL,
+            used_at INTEGER,
+            PRIMARY KEY (user_id, code_hash)
+        )",
+        [],
+    )?;
+
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS rooms (
+            id INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            description TEXT,
+            join_approval_required INTEGER NOT NULL DEFAULT 0
+        )",
+        [],
+    )?;
+
+    // To link users to rooms, `role` is one of 'owner', 'teacher' or 'student'
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS room_members (
+            room_id INTEGER NOT NULL,
+            user_id INTEGER NOT NULL,
+            role TEXT NOT NULL DEFAULT 'student',
+            PRIMARY KEY (room_id, user_id)
+        )",
+        [],
+    )?;
+
+    // Every room has exactly one owner
+    tx.execute(
+        "CREATE UNIQUE INDEX IF NOT EXISTS room_members_single_owner
+        ON room_members (room_id) WHERE role = 'owner'",
+        [],
+    )?;
+
+    // Users removed from a room that may not join it again
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS room_bans (
+            room_id INTEGER NOT NULL,
+            user_id INTEGER NOT NULL,
+            banned_by INTEGER NOT NULL,
+            banned_at INTEGER NOT NULL,
+            PRIMARY KEY (room_id, user_id)
+        )",
+        [],
+    )?;
+
+    // Requests to join rooms that require approval,
+    // `status` is one of 'pending', 'approved' or 'rejected'
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS join_requests (
+            room_id INTEGER NOT NULL,
+            user_id INTEGER NOT NULL,
+            status TEXT NOT NULL,
+            requested_at INTEGER NOT NULL,
+            decided_at INTEGER,
+            decided_by INTEGER,
+            PRIMARY KEY (room_id, user_id)
+        )",
+        [],
+    )?;
+
+    // Revoked, expired and used up codes are kept so joins can report why a code failed
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS invitation_codes (
+            code TEXT PRIMARY KEY,
+            room_id INTEGER NOT NULL,
+            created_at INTEGER NOT NULL,
+            expires_at INTEGER,
+            max_uses INTEGER,
+            uses INTEGER NOT NULL DEFAULT 0,
+            revoked_at INTEGER
+        )",
+        [],
+    )?;
+
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS assignments (
+            id INTEGER PRIMARY KEY,
+            room_id INTEGER NOT NULL,
+            title TEXT NOT NULL,
+            instructions TEXT NOT NULL,
+            due_date INTEGER,
+            max_points INTEGER NOT NULL
+        )",
+        [],
+    )?;
+
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS submissions (
+            id INTEGER PRIMARY KEY,
+            assignment_id INTEGER NOT NULL,
+            user_id INTEGER NOT NULL,
+            submitted_at INTEGER NOT NULL,
+            UNIQUE (assignment_id, user_id)
+        )",
+        [],
+    )?;
+
+    // Files are stored on disk, only their metadata lives here
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS submission_files (
+            id INTEGER PRIMARY KEY,
+            submission_id INTEGER NOT NULL,
+            file_name TEXT NOT NULL,
+            content_type TEXT NOT NULL,
+            size INTEGER NOT NULL,
+            storage_name TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // One grade per submission, `state` is either 'graded' or 'returned'
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS grades (
+            submission_id INTEGER PRIMARY KEY,
+            points REAL NOT NULL,
+            feedback TEXT NOT NULL,
+            state TEXT NOT NULL,
+            graded_by INTEGER NOT NULL,
+            graded_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Ties rows to the room or user they belong to, so deleting either removes everything that
+/// hangs off it instead of leaving rows behind that still resolve to it.
+fn foreign_keys(tx: &Transaction) -> rusqlite::Result<()> {
+    // Rooms used to be deleted without anything that belonged to them, those rows go now
+    tx.execute_batch(
+        "
+        DELETE FROM sessions WHERE user_id NOT IN (SELECT id FROM users);
+        DELETE FROM room_members
+        WHERE room_id NOT IN (SELECT id FROM rooms) OR user_id NOT IN (SELECT id FROM users);
+        DELETE FROM invitation_codes WHERE room_id NOT IN (SELECT id FROM rooms);
+        DELETE FROM room_bans
+        WHERE room_id NOT IN (SELECT id FROM rooms) OR user_id NOT IN (SELECT id FROM users);
+        DELETE FROM join_requests
+        WHERE room_id NOT IN (SELECT id FROM rooms) OR user_id NOT IN (SELECT id FROM users);
+        DELETE FROM assignments WHERE room_id NOT IN (SELECT id FROM rooms);
+        DELETE FROM submissions
+        WHERE assignment_id NOT IN (SELECT id FROM assignments) OR user_id NOT IN (SELECT id FROM users);
+        DELETE FROM submission_files WHERE submission_id NOT IN (SELECT id FROM submissions);
+        DELETE FROM grades WHERE submission_id NOT IN (SELECT id FROM submissions);
+        ",
+    )?;
+
+    rebuild(
+        tx,
+        "sessions",
+        "CREATE TABLE new_sessions (
+            id INTEGER PRIMARY KEY,
+            uuid TEXT NOT NULL UNIQUE,
+            user_id INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+            created_at INTEGER NOT NULL,
+            last_seen_at INTEGER NOT NULL,
+            expires_at INTEGER NOT NULL,
+            user_agent TEXT,
+            ip TEXT
+        )",
+    )?;
+
+    rebuild(
+        tx,
+        "room_members",
+        "CREATE TABLE new_room_members (
+            room_id INTEGER NOT NULL REFERENCES rooms (id) ON DELETE CASCADE,
+            user_id INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+            role TEXT NOT NULL DEFAULT 'student',
+            PRIMARY KEY (room_id, user_id)
+        )",
+    )?;
+
+    // Indexes go with the table they were on
+    tx.execute(
+        "CREATE UNIQUE INDEX room_members_single_owner
+        ON room_members (room_id) WHERE role = 'owner'",
+        [],
+    )?;
+
+    rebuild(
+        tx,
+        "invitation_codes",
+        "CREATE TABLE new_invitation_codes (
+            code TEXT PRIMARY KEY,
+            room_id INTEGER NOT NULL REFERENCES rooms (id) ON DELETE CASCADE,
+            created_at INTEGER NOT NULL,
+            expires_at INTEGER,
+            max_uses INTEGER,
+            uses INTEGER NOT NULL DEFAULT 0,
+            revoked_at INTEGER
+        )",
+    )?;
+
+    rebuild(
+        tx,
+        "room_bans",
+        "CREATE TABLE new_room_bans (
+            room_id INTEGER NOT NULL REFERENCES rooms (id) ON DELETE CASCADE,
+            user_id INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+            banned_by INTEGER NOT NULL,
+            banned_at INTEGER NOT NULL,
+            PRIMARY KEY (room_id, user_id)
+        )",
+    )?;
+
+    rebuild(
+        tx,
+        "join_requests",
+        "CREATE TABLE new_join_requests (
+            room_id INTEGER NOT NULL REFERENCES rooms (id) ON DELETE CASCADE,
+            user_id INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+            status TEXT NOT NULL,
+            requested_at INTEGER NOT NULL,
+            decided_at INTEGER,
+            decided_by INTEGER,
+            PRIMARY KEY (room_id, user_id)
+        )",
+    )?;
+
+    rebuild(
+        tx,
+        "assignments",
+        "CREATE TABLE new_assignments (
+            id INTEGER PRIMARY KEY,
+            room_id INTEGER NOT NULL REFERENCES rooms (id) ON DELETE CASCADE,
+            title TEXT NOT NULL,
+            instructions TEXT NOT NULL,
+            due_date INTEGER,
+            max_points INTEGER NOT NULL
+        )",
+    )?;
+
+    rebuild(
+        tx,
+        "submissions",
+        "CREATE TABLE new_submissions (
+            id INTEGER PRIMARY KEY,
+            assignment_id INTEGER NOT NULL REFERENCES assignments (id) ON DELETE CASCADE,
+            user_id INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+            submitted_at INTEGER NOT NULL,
+            UNIQUE (assignment_id, user_id)
+        )",
+    )?;
+
+    // Only the metadata goes, files on disk are left to whoever deleted the room
+    rebuild(
+        tx,
+        "submission_files",
+        "CREATE TABLE new_submission_files (
+            id INTEGER PRIMARY KEY,
+            submission_id INTEGER NOT NULL REFERENCES submissions (id) ON DELETE CASCADE,
+            file_name TEXT NOT NULL,
+            content_type TEXT NOT NULL,
+            size INTEGER NOT NULL,
+            storage_name TEXT NOT NULL
+        )",
+    )?;
+
+    rebuild(
+        tx,
+        "grades",
+        "CREATE TABLE new_grades (
+            submission_id INTEGER PRIMARY KEY REFERENCES submissions (id) ON DELETE CASCADE,
+            points REAL NOT NULL,
+            feedback TEXT NOT NULL,
+            state TEXT NOT NULL,
+            graded_by INTEGER NOT NULL,
+            graded_at INTEGER NOT NULL
+        )",
+    )?;
+
+    Ok(())
+}
+
+/// Lets rooms be deleted into a trash they can be restored from until they are purged.
+fn room_trash(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute("ALTER TABLE rooms ADD COLUMN deleted_at INTEGER", [])?;
+
+    Ok(())
+}
+
+/// Lets rooms of finished terms be archived, which keeps them around read-only.
+fn room_archiving(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute("ALTER TABLE rooms ADD COLUMN archived_at INTEGER", [])?;
+
+    Ok(())
+}
+
+/// Adds the settings owners can change besides the name, description and join approval.
+fn room_settings(tx: &Transaction) -> rusqlite::Result<()> {
+    // `visibility` is either 'visible' or 'hidden', `color` is `#rrggbb`
+    tx.execute_batch(
+        "
+        ALTER TABLE rooms ADD COLUMN visibility TEXT NOT NULL DEFAULT 'visible';
+        ALTER TABLE rooms ADD COLUMN color TEXT;
+        ALTER TABLE rooms ADD COLUMN section TEXT;
+        ALTER TABLE rooms ADD COLUMN term TEXT;
+        ",
+    )?;
+
+    Ok(())
+}
+
+/// Counts wrong passwords per submitted email instead of per user, so unknown emails
+/// lock the same way known ones do.
+fn login_failures_by_email(tx: &Transaction) -> rusqlite::Result<()> {
+    // Failures only matter for a few minutes, starting the counts over loses nothing
+    tx.execute_batch(
+        "
+        DROP TABLE login_failures;
+        CREATE TABLE login_failures (
+            email TEXT PRIMARY KEY,
+            failures INTEGER NOT NULL,
+            last_failed_at INTEGER NOT NULL,
+            locked_until INTEGER
+        );
+        ",
+    )?;
+
+    Ok(())
+}
+
+/// Remembers the code each join request was made with, so its use is only counted on approval.
+fn join_request_codes(tx: &Transaction) -> rusqlite::Result<()> {
+    // Requests from before this already counted their use when they were made, so theirs stays NULL
+    tx.execute("ALTER TABLE join_requests ADD COLUMN code TEXT", [])?;
+
+    Ok(())
+}
+
+/// Brings stored emails to the trimmed, lowercase form they are now looked up in.
+fn normalized_emails(tx: &Transaction) -> rusqlite::Result<()> {
+    let users: Vec<(i64, String)> = tx
+        .prepare("SELECT id, email FROM users")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+
+    for (id, email) in users {
+        // Spelled out rather than calling `utils::normalize_email`, which may change later
+        let normalized = email.trim().to_lowercase();
+        if normalized == email {
+            continue;
+        }
+
+        // Of accounts whose emails only differ in case the first keeps the email, the others
+        // are left as they are for an admin to sort out rather than being merged
+        tx.execute(
+            "UPDATE users SET email = ?1
+            WHERE id = ?2 AND NOT EXISTS (SELECT 1 FROM users WHERE email = ?1)",
+            params![normalized, id],
+        )?;
+    }
+
+    Ok(())
+}
@@ -1,19 +1,77 @@
+mod assignments;
+pub use assignments::CreateAssignmentOutcome;
+pub use assignments::GetAssignmentOutcome;
+pub use assignments::ListAssignmentsOutcome;
+
 mod database;
 pub use database::Database;
 
+mod grades;
+pub use grades::ExportGradebookOutcome;
+pub use grades::GetGradeOutcome;
+pub use grades::GetMyGradesOutcome;
+pub use grades::GradeSubmissionOutcome;
+
+mod migrations;
+pub use migrations::latest_schema_version;
+
+mod permissions;
+
 mod rooms;
 pub use rooms::CreateRoomOutcome;
+pub use rooms::DecideJoinRequestOutcome;
 pub use rooms::DeleteRoomOutcome;
+pub use rooms::GetMyJoinRequestsOutcome;
 pub use rooms::GetRoomOutcome;
 pub use rooms::InvitationCodeOutcome;
 pub use rooms::JoinRoomOutcome;
 pub use rooms::LeaveRoomOutcome;
+pub use rooms::ListTrashOutcome;
+pub use rooms::ListJoinRequestsOutcome;
+pub use rooms::ListMembersOutcome;
+pub use rooms::RemoveMemberOutcome;
+pub use rooms::RestoreRoomOutcome;
+pub use rooms::RevokeInvitationCodeOutcome;
+pub use rooms::RotateInvitationCodeOutcome;
+pub use rooms::SetArchivedOutcome;
+pub use rooms::SetJoinApprovalOutcome;
+pub use rooms::SetMemberRoleOutcome;
+pub use rooms::TransferOwnershipOutcome;
+pub use rooms::UpdateRoomOutcome;
 
 mod session;
+pub use session::CompleteTotpLoginOutcome;
+pub use session::ListSessionsOutcome;
 pub use session::LoginOutcome;
 pub use session::LogoutOutcome;
+pub use session::OidcLoginOutcome;
+pub use session::RevokeOtherSessionsOutcome;
+pub use session::RevokeSessionOutcome;
+pub use session::API_TOKEN_PREFIX;
+pub use session::SESSION_TTL;
+
+mod submissions;
+pub use submissions::CanSubmitOutcome;
+pub use submissions::CreateSubmissionOutcome;
+pub use submissions::GetSubmissionFileOutcome;
+pub use submissions::GetSubmissionOutcome;
+pub use submissions::ListSubmissionsOutcome;
+
+mod totp;
 
 mod user;
+pub use user::ChangePasswordOutcome;
+pub use user::ConfirmPasswordResetOutcome;
+pub use user::ConfirmTotpEnrollmentOutcome;
+pub use user::CreateApiTokenOutcome;
+pub use user::DisableTotpOutcome;
+pub use user::GetCurrentUserOutcome;
+pub use user::ListApiTokensOutcome;
 pub use user::RegisterOutcome;
+pub use user::RequestPasswordResetOutcome;
+pub use user::RevokeApiTokenOutcome;
+pub use user::StartTotpEnrollmentOutcome;
+pub use user::UpdateProfileOutcome;
+pub use user::VerifyEmailOutcome;
 
-mod utils;
+pub mod utils;
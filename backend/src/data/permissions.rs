@@ -0,0 +1,108 @@
+use deadpool_sqlite::rusqlite::{self, Connection, OptionalExtension, params};
+
+use crate::types::RoomRole;
+
+/// Actions inside a room that not every member may perform.
+#[derive(Clone, Copy)]
+pub enum Permission {
+    DeleteRoom,
+    ManageRoles,
+    TransferOwnership,
+    ManageInvitations,
+    ManageMembers,
+    ManageSettings,
+    ManageAssignments,
+    ViewAllSubmissions,
+    GradeSubmissions,
+    ExportGradebook,
+}
+
+impl RoomRole {
+    /// Whether a member with this role may perform the given action.
+    pub fn can(self, permission: Permission) -> bool {
+        match self {
+            Self::Owner => true,
+            Self::Teacher => !matches!(
+                permission,
+                Permission::DeleteRoom
+                    | Permission::ManageRoles
+                    | Permission::ManageSettings
+                    | Permission::TransferOwnership
+            ),
+            Self::Student => false,
+        }
+    }
+}
+
+/// Looks up the role of a user in a room, `None` if they are not a member or the room is in the trash.
+///
+/// Meant to be called from inside `interact`, e.g.
+/// `conn.interact(move |conn| permissions::room_role(conn, room_id, user_id))`.
+pub fn room_role(conn: &Connection, room_id: i32, user_id: i64) -> rusqlite::Result<Option<RoomRole>> {
+    conn.query_row(
+        "
+        SELECT rm.role
+        FROM room_members rm
+        JOIN rooms r ON r.id = rm.room_id
+        WHERE rm.room_id = ?1 AND rm.user_id = ?2 AND r.deleted_at IS NULL
+        ",
+        params![room_id, user_id],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+/// Checks whether a user is a member of a room with a role that grants the given permission.
+pub fn has_permission(
+    conn: &Connection,
+    room_id: i32,
+    user_id: i64,
+    permission: Permission,
+) -> rusqlite::Result<bool> {
+    Ok(room_role(conn, room_id, user_id)?.is_some_and(|role| role.can(permission)))
+}
+
+/// SQL condition for a room `r` not being hidden from the user whose membership of it is `rm`.
+///
+/// A hidden room is hidden from its students and anyone outside it, who may not see the room or
+/// anything in it. Queries over many rooms or their content join the user's membership as `rm`,
+/// with a `LEFT JOIN` a missing membership counts as hidden. The values are spelled the way
+/// [`RoomVisibility::Visible`](crate::types::RoomVisibility::Visible) and [`RoomRole::Student`] are stored.
+pub const NOT_HIDDEN: &str = "(r.visibility = 'visible' OR rm.role != 'student')";
+
+/// Checks whether a room is hidden from a user by the rule in [`NOT_HIDDEN`].
+///
+/// Rooms that do not exist count as hidden.
+pub fn is_hidden_from(conn: &Connection, room_id: i32, user_id: i64) -> rusqlite::Result<bool> {
+    let visible: bool = conn.query_row(
+        &format!(
+            "
+            SELECT EXISTS(
+                SELECT 1
+                FROM rooms r
+                LEFT JOIN room_members rm ON rm.room_id = r.id AND rm.user_id = ?2
+                WHERE r.id = ?1 AND {NOT_HIDDEN}
+            )
+            "
+        ),
+        params![room_id, user_id],
+        |row| row.get(0),
+    )?;
+
+    Ok(!visible)
+}
+
+/// Checks whether a room is archived, which leaves everything in it viewable but lets nobody
+/// join, submit, add assignments, grade, change roles, remove members or issue invitation codes.
+/// Members can still leave, and the owner can still edit the settings, transfer, unarchive or
+/// delete the room.
+pub fn is_archived(conn: &Connection, room_id: i32) -> rusqlite::Result<bool> {
+    Ok(conn
+        .query_row(
+            "SELECT archived_at IS NOT NULL FROM rooms WHERE id = ?1",
+            params![room_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .unwrap_or(false))
+}
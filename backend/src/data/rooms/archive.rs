@@ -0,0 +1,60 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::params;
+
+use super::super::Database;
+use super::super::session;
+use super::super::permissions::{self, Permission};
+use super::super::utils::now;
+
+pub enum SetArchivedOutcome {
+    Success,
+    NotLoggedIn,
+    NotOwner,
+}
+
+impl Database {
+    /// Attempts to archive or unarchive a room using the logged in user's ID and a given room ID.
+    ///
+    /// Archiving a room that already is archived keeps the time it was first archived at.
+    pub async fn set_room_archived(
+        &self,
+        session_uuid: String,
+        room_id: i32,
+        archived: bool,
+    ) -> Result<SetArchivedOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| session::user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(SetArchivedOutcome::NotLoggedIn);
+        };
+
+        let is_owner: bool = conn
+            .interact(move |conn| permissions::has_permission(conn, room_id, user_id, Permission::ManageSettings))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        if !is_owner {
+            return Ok(SetArchivedOutcome::NotOwner);
+        }
+
+        conn.interact(move |conn| {
+            if archived {
+                conn.execute(
+                    "UPDATE rooms SET archived_at = ?1 WHERE id = ?2 AND archived_at IS NULL",
+                    params![now(), room_id],
+                )
+            } else {
+                conn.execute("UPDATE rooms SET archived_at = NULL WHERE id = ?1", params![room_id])
+            }
+        })
+        .await
+        .map_err(|e| anyhow!("{e}"))??;
+
+        Ok(SetArchivedOutcome::Success)
+    }
+}
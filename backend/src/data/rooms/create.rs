@@ -1,8 +1,9 @@
 use anyhow::{anyhow, Result};
-use deadpool_sqlite::rusqlite::{Error, OptionalExtension, params};
+use deadpool_sqlite::rusqlite::{Error, params};
 
-use crate::types::NewRoom;
+use crate::types::{NewRoom, RoomRole};
 use super::super::Database;
+use super::super::session;
 
 pub enum CreateRoomOutcome {
     Success,
@@ -18,15 +19,8 @@ impl Database {
     ) -> Result<CreateRoomOutcome> {
         let conn = self.pool.get().await?;
 
-        let user_id: Option<i64> = conn
-            .interact(move |conn| {
-                conn.query_row(
-                    "SELECT user_id FROM sessions WHERE uuid = ?1",
-                    params![session_uuid],
-                    |row| row.get(0),
-                )
-                .optional()
-            })
+        let user_id = conn
+            .interact(move |conn| session::user_id(conn, &session_uuid))
             .await
             .map_err(|e| anyhow!("{e}"))??;
 
@@ -35,21 +29,26 @@ impl Database {
         };
 
         conn.interact(move |conn| {
+            let tx = conn.transaction()?;
+
             // Insert room
-            conn.execute(
-                "INSERT INTO rooms (owner, name, description) VALUES (?1, ?2, ?3)",
-                params![user_id, &room_data.name, &room_data.description],
+            tx.execute(
+                "INSERT INTO rooms (name, description, join_approval_required) VALUES (?1, ?2, ?3)",
+                params![&room_data.name, &room_data.description, room_data.join_approval_required],
             )?;
 
             // Get generated room id
-            let room_id = conn.last_insert_rowid();
+            let room_id = tx.last_insert_rowid();
 
             // Insert owner as room member
-            conn.execute(
-                "INSERT INTO room_members (room_id, user_id) VALUES (?1, ?2)",
-                params![room_id, user_id],
+            tx.execute(
+                "INSERT INTO room_members (room_id, user_id, role) VALUES (?1, ?2, ?3)",
+                params![room_id, user_id, RoomRole::Owner],
             )?;
 
+            // A room without its owner would be left behind if the second insert failed
+            tx.commit()?;
+
             Ok::<_, Error>(())
         })
         .await
@@ -0,0 +1,128 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::{OptionalExtension, params};
+
+use crate::types::JoinRequestStatus;
+use super::super::Database;
+use super::super::session;
+use super::super::permissions::{self, Permission};
+use super::super::utils;
+use super::join::count_code_use;
+
+pub enum DecideJoinRequestOutcome {
+    Success,
+    NotLoggedIn,
+    NotPermitted,
+    RequestNotFound,
+    RoomArchived,
+    /// The requester was banned from the room while their request was pending
+    Banned,
+    /// The code the request was made with was used up while the request was pending
+    CodeExhausted,
+}
+
+impl Database {
+    /// Attempts to approve or reject a pending join request using the logged in user's ID, a given room ID and requester ID.
+    ///
+    /// Approving adds the requester to the room as a student and counts a use of the code the
+    /// request was made with. Only owners and teachers of the room may decide on requests, and
+    /// requests to archived rooms or from banned users can only be rejected.
+    pub async fn decide_join_request(
+        &self,
+        session_uuid: String,
+        room_id: i32,
+        requester_id: i32,
+        approve: bool,
+    ) -> Result<DecideJoinRequestOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| session::user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(DecideJoinRequestOutcome::NotLoggedIn);
+        };
+
+        let is_permitted: bool = conn
+            .interact(move |conn| permissions::has_permission(conn, room_id, user_id, Permission::ManageMembers))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        if !is_permitted {
+            return Ok(DecideJoinRequestOutcome::NotPermitted);
+        }
+
+        if approve {
+            let archived: bool = conn
+                .interact(move |conn| permissions::is_archived(conn, room_id))
+                .await
+                .map_err(|e| anyhow!("{e}"))??;
+
+            if archived {
+                return Ok(DecideJoinRequestOutcome::RoomArchived);
+            }
+        }
+
+        let status = if approve {
+            JoinRequestStatus::Approved
+        } else {
+            JoinRequestStatus::Rejected
+        };
+
+        conn.interact(move |conn| {
+            let tx = conn.transaction()?;
+
+            let code: Option<Option<String>> = tx
+                .query_row(
+                    "SELECT code FROM join_requests WHERE room_id = ?1 AND user_id = ?2 AND status = ?3",
+                    params![room_id, requester_id, JoinRequestStatus::Pending],
+                    |row| row.get(0),
+                )
+                .optional()?;
+
+            let Some(code) = code else {
+                return Ok(DecideJoinRequestOutcome::RequestNotFound);
+            };
+
+            if approve {
+                let banned: bool = tx.query_row(
+                    "SELECT EXISTS(SELECT 1 FROM room_bans WHERE room_id = ?1 AND user_id = ?2)",
+                    params![room_id, requester_id],
+                    |row| row.get(0),
+                )?;
+
+                if banned {
+                    return Ok(DecideJoinRequestOutcome::Banned);
+                }
+
+                // Requests made before codes were remembered counted their use right away
+                if let Some(code) = code
+                    && !count_code_use(&tx, &code)?
+                {
+                    return Ok(DecideJoinRequestOutcome::CodeExhausted);
+                }
+            }
+
+            tx.execute(
+                "UPDATE join_requests SET status = ?1, decided_at = ?2, decided_by = ?3
+                WHERE room_id = ?4 AND user_id = ?5",
+                params![status, utils::now(), user_id, room_id, requester_id],
+            )?;
+
+            if approve {
+                tx.execute(
+                    "INSERT OR IGNORE INTO room_members (room_id, user_id) VALUES (?1, ?2)",
+                    params![room_id, requester_id],
+                )?;
+            }
+
+            tx.commit()?;
+
+            Ok::<_, deadpool_sqlite::rusqlite::Error>(DecideJoinRequestOutcome::Success)
+        })
+        .await
+        .map_err(|e| anyhow!("{e}"))?
+        .map_err(Into::into)
+    }
+}
@@ -1,7 +1,10 @@
 use anyhow::{anyhow, Result};
-use deadpool_sqlite::rusqlite::{OptionalExtension, params};
+use deadpool_sqlite::rusqlite::params;
 
 use super::super::Database;
+use super::super::session;
+use super::super::permissions::{self, Permission};
+use super::super::utils::now;
 
 pub enum DeleteRoomOutcome {
     Success,
@@ -11,6 +14,17 @@ pub enum DeleteRoomOutcome {
 
 impl Database {
     /// Attempts to delete a room using the logged in user's ID and a given room ID.
+    ///
+    /// The room is only moved to the trash, where its owner can restore it until
+    /// [`Database::purge_deleted_rooms`] removes it for good. Until then it is hidden from
+    /// everyone, and its invitation codes no longer work.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - a database connection cannot be acquired from the pool
+    /// - a database task fails to run or complete
+    /// - the required SQL queries fail to execute
     pub async fn delete_room(
         &self,
         session_uuid: String,
@@ -18,15 +32,8 @@ impl Database {
     ) -> Result<DeleteRoomOutcome> {
         let conn = self.pool.get().await?;
 
-        let user_id: Option<i64> = conn
-            .interact(move |conn| {
-                conn.query_row(
-                    "SELECT user_id FROM sessions WHERE uuid = ?1",
-                    params![session_uuid],
-                    |row| row.get(0),
-                )
-                .optional()
-            })
+        let user_id = conn
+            .interact(move |conn| session::user_id(conn, &session_uuid))
             .await
             .map_err(|e| anyhow!("{e}"))??;
 
@@ -34,20 +41,24 @@ impl Database {
             return Ok(DeleteRoomOutcome::NotLoggedIn);
         };
 
-        let affected = conn
-            .interact(move |conn| {
-                conn.execute(
-                    "DELETE FROM rooms WHERE id = ?1 AND owner = ?2",
-                    params![id, user_id],
-                )
-            })
+        let is_owner: bool = conn
+            .interact(move |conn| permissions::has_permission(conn, id, user_id, Permission::DeleteRoom))
             .await
             .map_err(|e| anyhow!("{e}"))??;
 
-        if affected == 0 {
+        if !is_owner {
             return Ok(DeleteRoomOutcome::NotOwner);
         }
 
+        conn.interact(move |conn| {
+            conn.execute(
+                "UPDATE rooms SET deleted_at = ?1 WHERE id = ?2 AND deleted_at IS NULL",
+                params![now(), id],
+            )
+        })
+        .await
+        .map_err(|e| anyhow!("{e}"))??;
+
         Ok(DeleteRoomOutcome::Success)
     }
 }
@@ -1,8 +1,10 @@
 use anyhow::{anyhow, Result};
-use deadpool_sqlite::rusqlite::{OptionalExtension, params};
+use deadpool_sqlite::rusqlite::params;
 
 use crate::types::Room;
 use super::super::Database;
+use super::super::session;
+use super::super::permissions;
 
 pub enum GetRoomOutcome {
     Success(Vec<Room>),
@@ -11,18 +13,14 @@ pub enum GetRoomOutcome {
 
 impl Database {
     /// Attempts to get rooms current user is a member of using the logged in user's ID.
-    pub async fn get_rooms(&self, session_uuid: String) -> Result<GetRoomOutcome> {
+    ///
+    /// With `archived` set, only archived or only active rooms are returned.
+    /// Students do not see hidden rooms.
+    pub async fn get_rooms(&self, session_uuid: String, archived: Option<bool>) -> Result<GetRoomOutcome> {
         let conn = self.pool.get().await?;
 
-        let user_id: Option<i64> = conn
-            .interact(move |conn| {
-                conn.query_row(
-                    "SELECT user_id FROM sessions WHERE uuid = ?1",
-                    params![session_uuid],
-                    |row| row.get(0),
-                )
-                .optional()
-            })
+        let user_id = conn
+            .interact(move |conn| session::user_id(conn, &session_uuid))
             .await
             .map_err(|e| anyhow!("{e}"))??;
 
@@ -32,20 +30,32 @@ impl Database {
 
         let rooms = conn
             .interact(move |conn| -> Result<Vec<Room>> {
-                let mut stmt = conn.prepare(
+                let mut stmt = conn.prepare(&format!(
                     "
-                    SELECT r.id, r.name, r.description
+                    SELECT r.id, r.name, r.description, r.join_approval_required, r.archived_at,
+                        r.visibility, r.color, r.section, r.term, rm.role
                     FROM rooms r
                     JOIN room_members rm ON rm.room_id = r.id
                     WHERE rm.user_id = ?1
+                        AND r.deleted_at IS NULL
+                        AND (?2 IS NULL OR (r.archived_at IS NOT NULL) = ?2)
+                        AND {}
                     ",
-                )?;
+                    permissions::NOT_HIDDEN,
+                ))?;
 
-                let rooms_iter = stmt.query_map(params![user_id], |row| {
+                let rooms_iter = stmt.query_map(params![user_id, archived], |row| {
                     Ok(Room {
                         id: row.get(0)?,
                         name: row.get(1)?,
                         description: row.get(2)?,
+                        join_approval_required: row.get(3)?,
+                        archived_at: row.get(4)?,
+                        visibility: row.get(5)?,
+                        color: row.get(6)?,
+                        section: row.get(7)?,
+                        term: row.get(8)?,
+                        role: row.get(9)?,
                     })
                 })?;
 
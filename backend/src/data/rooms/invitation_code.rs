@@ -1,33 +1,41 @@
 use anyhow::{anyhow, Result};
-use deadpool_sqlite::rusqlite::{OptionalExtension, params};
+use deadpool_sqlite::rusqlite::{self, Connection, OptionalExtension, params};
 
+use crate::types::{ApiTokenScope, InvitationCode, NewInvitationCode};
 use super::super::Database;
+use super::super::session::{self, API_TOKEN_PREFIX};
+use super::super::permissions::{self, Permission};
 use super::super::utils;
 
 pub enum InvitationCodeOutcome {
-    Success(String),
+    Success(InvitationCode),
     NotLoggedIn,
-    NotOwner,
+    NotPermitted,
+    /// There is no usable code and the credential is a read-only API token, which may not create one
+    InsufficientScope,
+    /// There is no usable code and the room is archived, which gets no new ones
+    RoomArchived,
 }
 
 impl Database {
     /// Attempts to get or create an invitation code for a room using the logged-in user's ID and a given room ID.
+    ///
+    /// Returns the room's currently usable code. If there is none, because the last one was
+    /// revoked, expired or used up, a new code without expiry or usage limit is created,
+    /// unless the credential is a read-only API token or the room is archived.
     pub async fn get_invitation_code(
         &self,
         session_uuid: String,
         room_id: i32,
     ) -> Result<InvitationCodeOutcome> {
+        // Only `GET` is checked for read-only tokens, but this one can write
+        let read_only = session_uuid.starts_with(API_TOKEN_PREFIX)
+            && self.api_token_scope(session_uuid.clone()).await? == Some(ApiTokenScope::Read);
+
         let conn = self.pool.get().await?;
 
-        let user_id: Option<i64> = conn
-            .interact(move |conn| {
-                conn.query_row(
-                    "SELECT user_id FROM sessions WHERE uuid = ?1",
-                    params![session_uuid],
-                    |row| row.get(0),
-                )
-                .optional()
-            })
+        let user_id = conn
+            .interact(move |conn| session::user_id(conn, &session_uuid))
             .await
             .map_err(|e| anyhow!("{e}"))??;
 
@@ -35,27 +43,38 @@ impl Database {
             return Ok(InvitationCodeOutcome::NotLoggedIn);
         };
 
-        let is_owner: bool = conn
-            .interact(move |conn| {
-                conn.query_row(
-                    "SELECT EXISTS(SELECT 1 FROM rooms WHERE id = ?1 AND owner = ?2)",
-                    params![room_id, user_id],
-                    |row| row.get(0),
-                )
-            })
+        let is_permitted: bool = conn
+            .interact(move |conn| permissions::has_permission(conn, room_id, user_id, Permission::ManageInvitations))
             .await
             .map_err(|e| anyhow!("{e}"))??;
 
-        if !is_owner {
-            return Ok(InvitationCodeOutcome::NotOwner);
+        if !is_permitted {
+            return Ok(InvitationCodeOutcome::NotPermitted);
         }
 
         if let Some(code) = conn
             .interact(move |conn| {
                 conn.query_row(
-                    "SELECT code FROM invitation_codes WHERE room_id = ?1",
-                    params![room_id],
-                    |row| row.get(0),
+                    "
+                    SELECT code, created_at, expires_at, max_uses, uses
+                    FROM invitation_codes
+                    WHERE room_id = ?1
+                        AND revoked_at IS NULL
+                        AND (expires_at IS NULL OR expires_at > ?2)
+                        AND (max_uses IS NULL OR uses < max_uses)
+                    ORDER BY created_at DESC
+                    LIMIT 1
+                    ",
+                    params![room_id, utils::now()],
+                    |row| {
+                        Ok(InvitationCode {
+                            code: row.get(0)?,
+                            created_at: row.get(1)?,
+                            expires_at: row.get(2)?,
+                            max_uses: row.get(3)?,
+                            uses: row.get(4)?,
+                        })
+                    },
                 )
                 .optional()
             })
@@ -65,36 +84,62 @@ impl Database {
             return Ok(InvitationCodeOutcome::Success(code));
         }
 
-        let code: String = loop {
-            let candidate = utils::generate_invitation_code();
-
-            let candidate_clone = candidate.clone();
-            let exists: bool = conn
-                .interact(move |conn| {
-                    conn.query_row(
-                        "SELECT EXISTS(SELECT 1 FROM invitation_codes WHERE code = ?1)",
-                        params![candidate_clone],
-                        |row| row.get(0),
-                    )
-                })
-                .await
-                .map_err(|e| anyhow!("{e}"))??;
-
-            if !exists {
-                break candidate;
-            }
-        };
+        if read_only {
+            return Ok(InvitationCodeOutcome::InsufficientScope);
+        }
 
-        let code_clone = code.clone();
-        conn.interact(move |conn| {
-            conn.execute(
-                "INSERT INTO invitation_codes (room_id, code) VALUES (?1, ?2)",
-                params![room_id, code_clone],
-            )
-        })
-        .await
-        .map_err(|e| anyhow!("{e}"))??;
+        let archived: bool = conn
+            .interact(move |conn| permissions::is_archived(conn, room_id))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        if archived {
+            return Ok(InvitationCodeOutcome::RoomArchived);
+        }
+
+        let code = conn
+            .interact(move |conn| insert_invitation_code(conn, room_id, &NewInvitationCode::default()))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
 
         Ok(InvitationCodeOutcome::Success(code))
     }
 }
+
+/// Generates a new unique code for a room and stores it with the given limits.
+pub(super) fn insert_invitation_code(
+    conn: &Connection,
+    room_id: i32,
+    settings: &NewInvitationCode,
+) -> rusqlite::Result<InvitationCode> {
+    let code: String = loop {
+        let candidate = utils::generate_invitation_code();
+
+        let exists: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM invitation_codes WHERE code = ?1)",
+            params![candidate],
+            |row| row.get(0),
+        )?;
+
+        if !exists {
+            break candidate;
+        }
+    };
+
+    let created_at = utils::now();
+    let expires_at = settings.expires_in.map(|secs| created_at.saturating_add(secs));
+
+    conn.execute(
+        "INSERT INTO invitation_codes (code, room_id, created_at, expires_at, max_uses)
+        VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![code, room_id, created_at, expires_at, settings.max_uses],
+    )?;
+
+    Ok(InvitationCode {
+        code,
+        created_at,
+        expires_at,
+        max_uses: settings.max_uses,
+        uses: 0,
+    })
+}
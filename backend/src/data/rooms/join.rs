@@ -1,55 +1,150 @@
 use anyhow::{anyhow, Result};
-use deadpool_sqlite::rusqlite::{OptionalExtension, params};
+use deadpool_sqlite::rusqlite::{self, Connection, OptionalExtension, params};
 
+use crate::types::JoinRequestStatus;
 use super::super::Database;
+use super::super::session;
+use super::super::permissions;
+use super::super::utils;
 
 pub enum JoinRoomOutcome {
     Success,
+    RequestPending,
+    RequestAlreadyPending,
     AlreadyMember,
     InvalidCode,
+    CodeRevoked,
+    CodeExpired,
+    CodeExhausted,
     NotLoggedIn,
+    Banned,
+    EmailNotVerified,
+    RoomArchived,
+}
+
+struct Invitation {
+    room_id: i32,
+    archived: bool,
+    revoked: bool,
+    expires_at: Option<i64>,
+    max_uses: Option<i32>,
+    uses: i32,
 }
 
 impl Database {
     /// Attempts to join a room using the logged-in user's ID and a given room invitation code.
+    ///
+    /// If the room requires approval, a pending join request is created instead and
+    /// [`JoinRoomOutcome::RequestPending`] is returned.
+    /// If `require_verified_email` is set, users who have not verified their email may not join.
+    /// Codes of rooms in the trash or hidden from the user are reported as
+    /// [`JoinRoomOutcome::InvalidCode`], archived rooms cannot be joined at all.
     pub async fn join_room(
         &self,
         session_uuid: String,
         code: String,
+        require_verified_email: bool,
     ) -> Result<JoinRoomOutcome> {
         let conn = self.pool.get().await?;
 
-        let user_id: Option<i64> = conn
+        let user_id = conn
+            .interact(move |conn| session::user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(JoinRoomOutcome::NotLoggedIn);
+        };
+
+        if require_verified_email {
+            let email_verified: bool = conn
+                .interact(move |conn| {
+                    conn.query_row(
+                        "SELECT email_verified_at IS NOT NULL FROM users WHERE id = ?1",
+                        params![user_id],
+                        |row| row.get(0),
+                    )
+                })
+                .await
+                .map_err(|e| anyhow!("{e}"))??;
+
+            if !email_verified {
+                return Ok(JoinRoomOutcome::EmailNotVerified);
+            }
+        }
+
+        let code_clone = code.clone();
+        let invitation: Option<Invitation> = conn
             .interact(move |conn| {
                 conn.query_row(
-                    "SELECT user_id FROM sessions WHERE uuid = ?1",
-                    params![session_uuid],
-                    |row| row.get(0),
+                    "
+                    SELECT c.room_id, r.archived_at IS NOT NULL, c.revoked_at IS NOT NULL, c.expires_at, c.max_uses, c.uses
+                    FROM invitation_codes c
+                    JOIN rooms r ON r.id = c.room_id
+                    WHERE c.code = ?1 AND r.deleted_at IS NULL
+                    ",
+                    params![code_clone],
+                    |row| {
+                        Ok(Invitation {
+                            room_id: row.get(0)?,
+                            archived: row.get(1)?,
+                            revoked: row.get(2)?,
+                            expires_at: row.get(3)?,
+                            max_uses: row.get(4)?,
+                            uses: row.get(5)?,
+                        })
+                    },
                 )
                 .optional()
             })
             .await
             .map_err(|e| anyhow!("{e}"))??;
 
-        let Some(user_id) = user_id else {
-            return Ok(JoinRoomOutcome::NotLoggedIn);
+        let Some(invitation) = invitation else {
+            return Ok(JoinRoomOutcome::InvalidCode);
         };
 
-        let room_id: Option<i32> = conn
+        let room_id = invitation.room_id;
+
+        let hidden: bool = conn
+            .interact(move |conn| permissions::is_hidden_from(conn, room_id, user_id))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        if hidden {
+            return Ok(JoinRoomOutcome::InvalidCode);
+        }
+
+        if invitation.archived {
+            return Ok(JoinRoomOutcome::RoomArchived);
+        }
+
+        if invitation.revoked {
+            return Ok(JoinRoomOutcome::CodeRevoked);
+        }
+
+        if invitation.expires_at.is_some_and(|expires_at| expires_at <= utils::now()) {
+            return Ok(JoinRoomOutcome::CodeExpired);
+        }
+
+        if invitation.max_uses.is_some_and(|max_uses| invitation.uses >= max_uses) {
+            return Ok(JoinRoomOutcome::CodeExhausted);
+        }
+
+        let banned: bool = conn
             .interact(move |conn| {
                 conn.query_row(
-                    "SELECT room_id FROM invitation_codes WHERE code = ?1",
-                    params![code],
+                    "SELECT EXISTS(SELECT 1 FROM room_bans WHERE room_id = ?1 AND user_id = ?2)",
+                    params![room_id, user_id],
                     |row| row.get(0),
                 )
-                .optional()
             })
             .await
             .map_err(|e| anyhow!("{e}"))??;
 
-        let Some(room_id) = room_id else {
-            return Ok(JoinRoomOutcome::InvalidCode);
-        };
+        if banned {
+            return Ok(JoinRoomOutcome::Banned);
+        }
 
         let already_member: bool = conn
             .interact(move |conn| {
@@ -66,15 +161,87 @@ impl Database {
             return Ok(JoinRoomOutcome::AlreadyMember);
         }
 
+        let approval_required: Option<bool> = conn
+            .interact(move |conn| {
+                conn.query_row(
+                    "SELECT join_approval_required FROM rooms WHERE id = ?1 AND deleted_at IS NULL",
+                    params![room_id],
+                    |row| row.get(0),
+                )
+                .optional()
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(approval_required) = approval_required else {
+            return Ok(JoinRoomOutcome::InvalidCode);
+        };
+
+        if approval_required {
+            let already_pending: bool = conn
+                .interact(move |conn| {
+                    conn.query_row(
+                        "SELECT EXISTS(SELECT 1 FROM join_requests WHERE room_id = ?1 AND user_id = ?2 AND status = ?3)",
+                        params![room_id, user_id, JoinRequestStatus::Pending],
+                        |row| row.get(0),
+                    )
+                })
+                .await
+                .map_err(|e| anyhow!("{e}"))??;
+
+            if already_pending {
+                return Ok(JoinRoomOutcome::RequestAlreadyPending);
+            }
+        }
+
         conn.interact(move |conn| {
-            conn.execute(
+            if approval_required {
+                // A rejected user may ask again, which resets their request. The code is only
+                // counted as used once the request is approved
+                conn.execute(
+                    "INSERT INTO join_requests (room_id, user_id, status, requested_at, code)
+                    VALUES (?1, ?2, ?3, ?4, ?5)
+                    ON CONFLICT(room_id, user_id) DO UPDATE SET
+                        status = excluded.status,
+                        requested_at = excluded.requested_at,
+                        decided_at = NULL,
+                        decided_by = NULL,
+                        code = excluded.code",
+                    params![room_id, user_id, JoinRequestStatus::Pending, utils::now(), code],
+                )?;
+
+                return Ok(JoinRoomOutcome::RequestPending);
+            }
+
+            let tx = conn.transaction()?;
+
+            if !count_code_use(&tx, &code)? {
+                return Ok(JoinRoomOutcome::CodeExhausted);
+            }
+
+            tx.execute(
                 "INSERT INTO room_members (room_id, user_id) VALUES (?1, ?2)",
                 params![room_id, user_id],
-            )
+            )?;
+
+            tx.commit()?;
+
+            Ok::<_, deadpool_sqlite::rusqlite::Error>(JoinRoomOutcome::Success)
         })
         .await
-        .map_err(|e| anyhow!("{e}"))??;
-
-        Ok(JoinRoomOutcome::Success)
+        .map_err(|e| anyhow!("{e}"))?
+        .map_err(Into::into)
     }
 }
+
+/// Counts a use of an invitation code, `false` if it was used up in the meantime.
+pub(super) fn count_code_use(conn: &Connection, code: &str) -> rusqlite::Result<bool> {
+    // Guards against the code being used up by concurrent joins since it was checked
+    let counted = conn.execute(
+        "UPDATE invitation_codes SET uses = uses + 1
+        WHERE code = ?1 AND (max_uses IS NULL OR uses < max_uses)",
+        params![code],
+    )?;
+
+    Ok(counted > 0)
+}
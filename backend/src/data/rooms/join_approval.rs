@@ -0,0 +1,57 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::params;
+
+use crate::types::JoinApproval;
+use super::super::Database;
+use super::super::session;
+use super::super::permissions::{self, Permission};
+
+pub enum SetJoinApprovalOutcome {
+    Success,
+    NotLoggedIn,
+    NotOwner,
+}
+
+impl Database {
+    /// Attempts to change whether joining a room requires approval using the logged in user's ID and a given room ID.
+    ///
+    /// Turning approval off does not touch requests that are already pending, they can still be
+    /// approved or rejected.
+    pub async fn set_join_approval(
+        &self,
+        session_uuid: String,
+        room_id: i32,
+        approval: JoinApproval,
+    ) -> Result<SetJoinApprovalOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| session::user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(SetJoinApprovalOutcome::NotLoggedIn);
+        };
+
+        let is_owner: bool = conn
+            .interact(move |conn| permissions::has_permission(conn, room_id, user_id, Permission::ManageSettings))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        if !is_owner {
+            return Ok(SetJoinApprovalOutcome::NotOwner);
+        }
+
+        conn.interact(move |conn| {
+            conn.execute(
+                "UPDATE rooms SET join_approval_required = ?1 WHERE id = ?2",
+                params![approval.required, room_id],
+            )
+        })
+        .await
+        .map_err(|e| anyhow!("{e}"))??;
+
+        Ok(SetJoinApprovalOutcome::Success)
+    }
+}
@@ -0,0 +1,83 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::params;
+
+use crate::types::{JoinRequest, JoinRequestStatus};
+use super::super::Database;
+use super::super::session;
+use super::super::permissions::{self, Permission};
+
+pub enum ListJoinRequestsOutcome {
+    Success(Vec<JoinRequest>),
+    NotLoggedIn,
+    NotPermitted,
+}
+
+impl Database {
+    /// Attempts to list the pending join requests of a room using the logged in user's ID and a given room ID.
+    ///
+    /// Only owners and teachers of the room may see them.
+    pub async fn list_join_requests(
+        &self,
+        session_uuid: String,
+        room_id: i32,
+    ) -> Result<ListJoinRequestsOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| session::user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(ListJoinRequestsOutcome::NotLoggedIn);
+        };
+
+        let is_permitted: bool = conn
+            .interact(move |conn| permissions::has_permission(conn, room_id, user_id, Permission::ManageMembers))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        if !is_permitted {
+            return Ok(ListJoinRequestsOutcome::NotPermitted);
+        }
+
+        let requests = conn
+            .interact(move |conn| -> Result<Vec<JoinRequest>> {
+                let mut stmt = conn.prepare(
+                    "
+                    SELECT r.id, r.name, u.id, u.name, u.surname, u.email, jr.status, jr.requested_at, jr.decided_at
+                    FROM join_requests jr
+                    JOIN rooms r ON r.id = jr.room_id
+                    JOIN users u ON u.id = jr.user_id
+                    WHERE jr.room_id = ?1 AND jr.status = ?2
+                    ORDER BY jr.requested_at
+                    ",
+                )?;
+
+                let requests_iter = stmt.query_map(params![room_id, JoinRequestStatus::Pending], |row| {
+                    Ok(JoinRequest {
+                        room_id: row.get(0)?,
+                        room_name: row.get(1)?,
+                        user_id: row.get(2)?,
+                        name: row.get(3)?,
+                        surname: row.get(4)?,
+                        email: row.get(5)?,
+                        status: row.get(6)?,
+                        requested_at: row.get(7)?,
+                        decided_at: row.get(8)?,
+                    })
+                })?;
+
+                let mut requests = Vec::new();
+                for request in requests_iter {
+                    requests.push(request?);
+                }
+
+                Ok(requests)
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        Ok(ListJoinRequestsOutcome::Success(requests))
+    }
+}
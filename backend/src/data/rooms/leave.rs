@@ -1,7 +1,10 @@
 use anyhow::{anyhow, Result};
-use deadpool_sqlite::rusqlite::{OptionalExtension, params};
+use deadpool_sqlite::rusqlite::params;
 
+use crate::types::RoomRole;
 use super::super::Database;
+use super::super::session;
+use super::super::permissions;
 
 pub enum LeaveRoomOutcome {
     Success,
@@ -19,15 +22,8 @@ impl Database {
     ) -> Result<LeaveRoomOutcome> {
         let conn = self.pool.get().await?;
 
-        let user_id: Option<i64> = conn
-            .interact(move |conn| {
-                conn.query_row(
-                    "SELECT user_id FROM sessions WHERE uuid = ?1",
-                    params![session_uuid],
-                    |row| row.get(0),
-                )
-                .optional()
-            })
+        let user_id = conn
+            .interact(move |conn| session::user_id(conn, &session_uuid))
             .await
             .map_err(|e| anyhow!("{e}"))??;
 
@@ -35,34 +31,25 @@ impl Database {
             return Ok(LeaveRoomOutcome::NotLoggedIn);
         };
 
-        let is_owner: bool = conn
-            .interact(move |conn| {
-                conn.query_row(
-                    "SELECT EXISTS(SELECT 1 FROM rooms WHERE id = ?1 AND owner = ?2)",
-                    params![room_id, user_id],
-                    |row| row.get(0),
-                )
-            })
+        let role = conn
+            .interact(move |conn| permissions::room_role(conn, room_id, user_id))
             .await
             .map_err(|e| anyhow!("{e}"))??;
 
-        if is_owner {
-            return Ok(LeaveRoomOutcome::OwnerCannotLeave);
+        match role {
+            None => return Ok(LeaveRoomOutcome::NotMember),
+            Some(RoomRole::Owner) => return Ok(LeaveRoomOutcome::OwnerCannotLeave),
+            Some(RoomRole::Teacher | RoomRole::Student) => {}
         }
 
-        let affected = conn
-            .interact(move |conn| {
-                conn.execute(
-                    "DELETE FROM room_members WHERE room_id = ?1 AND user_id = ?2",
-                    params![room_id, user_id],
-                )
-            })
-            .await
-            .map_err(|e| anyhow!("{e}"))??;
-
-        if affected == 0 {
-            return Ok(LeaveRoomOutcome::NotMember);
-        }
+        conn.interact(move |conn| {
+            conn.execute(
+                "DELETE FROM room_members WHERE room_id = ?1 AND user_id = ?2",
+                params![room_id, user_id],
+            )
+        })
+        .await
+        .map_err(|e| anyhow!("{e}"))??;
 
         Ok(LeaveRoomOutcome::Success)
     }
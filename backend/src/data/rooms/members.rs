@@ -0,0 +1,78 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::params;
+
+use crate::types::RoomMember;
+use super::super::Database;
+use super::super::session;
+use super::super::permissions::{self, Permission};
+
+pub enum ListMembersOutcome {
+    Success(Vec<RoomMember>),
+    NotLoggedIn,
+    NotPermitted,
+}
+
+impl Database {
+    /// Attempts to list the members of a room using the logged in user's ID and a given room ID.
+    ///
+    /// Only owners and teachers of the room may see its roster.
+    pub async fn list_members(
+        &self,
+        session_uuid: String,
+        room_id: i32,
+    ) -> Result<ListMembersOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| session::user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(ListMembersOutcome::NotLoggedIn);
+        };
+
+        let is_permitted: bool = conn
+            .interact(move |conn| permissions::has_permission(conn, room_id, user_id, Permission::ManageMembers))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        if !is_permitted {
+            return Ok(ListMembersOutcome::NotPermitted);
+        }
+
+        let members = conn
+            .interact(move |conn| -> Result<Vec<RoomMember>> {
+                let mut stmt = conn.prepare(
+                    "
+                    SELECT u.id, u.name, u.surname, u.email, rm.role
+                    FROM room_members rm
+                    JOIN users u ON u.id = rm.user_id
+                    WHERE rm.room_id = ?1
+                    ORDER BY u.surname, u.name, u.id
+                    ",
+                )?;
+
+                let members_iter = stmt.query_map(params![room_id], |row| {
+                    Ok(RoomMember {
+                        user_id: row.get(0)?,
+                        name: row.get(1)?,
+                        surname: row.get(2)?,
+                        email: row.get(3)?,
+                        role: row.get(4)?,
+                    })
+                })?;
+
+                let mut members = Vec::new();
+                for member in members_iter {
+                    members.push(member?);
+                }
+
+                Ok(members)
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        Ok(ListMembersOutcome::Success(members))
+    }
+}
@@ -1,6 +1,12 @@
+mod archive;
+pub use archive::SetArchivedOutcome;
+
 mod create;
 pub use create::CreateRoomOutcome;
 
+mod decide_join_request;
+pub use decide_join_request::DecideJoinRequestOutcome;
+
 mod delete;
 pub use delete::DeleteRoomOutcome;
 
@@ -13,5 +19,38 @@ pub use invitation_code::InvitationCodeOutcome;
 mod join;
 pub use join::JoinRoomOutcome;
 
+mod join_approval;
+pub use join_approval::SetJoinApprovalOutcome;
+
+mod join_requests;
+pub use join_requests::ListJoinRequestsOutcome;
+
 mod leave;
 pub use leave::LeaveRoomOutcome;
+
+mod members;
+pub use members::ListMembersOutcome;
+
+mod my_join_requests;
+pub use my_join_requests::GetMyJoinRequestsOutcome;
+
+mod remove_member;
+pub use remove_member::RemoveMemberOutcome;
+
+mod revoke_invitation_code;
+pub use revoke_invitation_code::RevokeInvitationCodeOutcome;
+
+mod role;
+pub use role::SetMemberRoleOutcome;
+
+mod rotate_invitation_code;
+pub use rotate_invitation_code::RotateInvitationCodeOutcome;
+
+mod transfer;
+pub use transfer::TransferOwnershipOutcome;
+
+mod trash;
+pub use trash::{ListTrashOutcome, RestoreRoomOutcome};
+
+mod update;
+pub use update::UpdateRoomOutcome;
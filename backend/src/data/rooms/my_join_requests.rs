@@ -0,0 +1,71 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::params;
+
+use crate::types::JoinRequest;
+use super::super::Database;
+use super::super::session;
+use super::super::permissions;
+
+pub enum GetMyJoinRequestsOutcome {
+    Success(Vec<JoinRequest>),
+    NotLoggedIn,
+}
+
+impl Database {
+    /// Attempts to get all join requests made by the logged in user, whatever their status.
+    ///
+    /// Requests for rooms that are hidden from the user are left out.
+    pub async fn get_my_join_requests(&self, session_uuid: String) -> Result<GetMyJoinRequestsOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| session::user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(GetMyJoinRequestsOutcome::NotLoggedIn);
+        };
+
+        let requests = conn
+            .interact(move |conn| -> Result<Vec<JoinRequest>> {
+                let mut stmt = conn.prepare(&format!(
+                    "
+                    SELECT r.id, r.name, u.id, u.name, u.surname, u.email, jr.status, jr.requested_at, jr.decided_at
+                    FROM join_requests jr
+                    JOIN rooms r ON r.id = jr.room_id
+                    JOIN users u ON u.id = jr.user_id
+                    LEFT JOIN room_members rm ON rm.room_id = r.id AND rm.user_id = jr.user_id
+                    WHERE jr.user_id = ?1 AND r.deleted_at IS NULL AND {}
+                    ORDER BY jr.requested_at DESC
+                    ",
+                    permissions::NOT_HIDDEN,
+                ))?;
+
+                let requests_iter = stmt.query_map(params![user_id], |row| {
+                    Ok(JoinRequest {
+                        room_id: row.get(0)?,
+                        room_name: row.get(1)?,
+                        user_id: row.get(2)?,
+                        name: row.get(3)?,
+                        surname: row.get(4)?,
+                        email: row.get(5)?,
+                        status: row.get(6)?,
+                        requested_at: row.get(7)?,
+                        decided_at: row.get(8)?,
+                    })
+                })?;
+
+                let mut requests = Vec::new();
+                for request in requests_iter {
+                    requests.push(request?);
+                }
+
+                Ok(requests)
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        Ok(GetMyJoinRequestsOutcome::Success(requests))
+    }
+}
@@ -0,0 +1,96 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::params;
+
+use crate::types::RoomRole;
+use super::super::Database;
+use super::super::session;
+use super::super::permissions::{self, Permission};
+use super::super::utils;
+
+pub enum RemoveMemberOutcome {
+    Success,
+    NotLoggedIn,
+    NotPermitted,
+    NotMember,
+    CannotRemoveOwner,
+    RoomArchived,
+}
+
+impl Database {
+    /// Attempts to remove a member from a room using the logged in user's ID, a given room ID and member ID.
+    ///
+    /// Owners may remove teachers and students, teachers may only remove students. The owner
+    /// can never be removed. When `ban` is set the user is also prevented from joining the room
+    /// again with any invitation code. Nobody can be removed from an archived room.
+    pub async fn remove_member(
+        &self,
+        session_uuid: String,
+        room_id: i32,
+        member_id: i32,
+        ban: bool,
+    ) -> Result<RemoveMemberOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| session::user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(RemoveMemberOutcome::NotLoggedIn);
+        };
+
+        let role = conn
+            .interact(move |conn| permissions::room_role(conn, room_id, user_id))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(role) = role.filter(|role| role.can(Permission::ManageMembers)) else {
+            return Ok(RemoveMemberOutcome::NotPermitted);
+        };
+
+        let archived: bool = conn
+            .interact(move |conn| permissions::is_archived(conn, room_id))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        if archived {
+            return Ok(RemoveMemberOutcome::RoomArchived);
+        }
+
+        let member_role = conn
+            .interact(move |conn| permissions::room_role(conn, room_id, i64::from(member_id)))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        match (role, member_role) {
+            (_, None) => return Ok(RemoveMemberOutcome::NotMember),
+            (_, Some(RoomRole::Owner)) => return Ok(RemoveMemberOutcome::CannotRemoveOwner),
+            (RoomRole::Teacher, Some(RoomRole::Teacher)) => return Ok(RemoveMemberOutcome::NotPermitted),
+            _ => {}
+        }
+
+        conn.interact(move |conn| {
+            let tx = conn.transaction()?;
+
+            tx.execute(
+                "DELETE FROM room_members WHERE room_id = ?1 AND user_id = ?2",
+                params![room_id, member_id],
+            )?;
+
+            if ban {
+                tx.execute(
+                    "INSERT OR IGNORE INTO room_bans (room_id, user_id, banned_by, banned_at)
+                    VALUES (?1, ?2, ?3, ?4)",
+                    params![room_id, member_id, user_id, utils::now()],
+                )?;
+            }
+
+            tx.commit()
+        })
+        .await
+        .map_err(|e| anyhow!("{e}"))??;
+
+        Ok(RemoveMemberOutcome::Success)
+    }
+}
@@ -0,0 +1,66 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::params;
+
+use super::super::Database;
+use super::super::session;
+use super::super::permissions::{self, Permission};
+use super::super::utils;
+
+pub enum RevokeInvitationCodeOutcome {
+    Success,
+    NotLoggedIn,
+    NotPermitted,
+    RoomArchived,
+}
+
+impl Database {
+    /// Attempts to revoke the invitation codes of a room using the logged in user's ID and a given room ID.
+    ///
+    /// Afterwards nobody can join the room until a new code is requested. The codes of archived
+    /// rooms are left as they are.
+    pub async fn revoke_invitation_code(
+        &self,
+        session_uuid: String,
+        room_id: i32,
+    ) -> Result<RevokeInvitationCodeOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| session::user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(RevokeInvitationCodeOutcome::NotLoggedIn);
+        };
+
+        let is_permitted: bool = conn
+            .interact(move |conn| permissions::has_permission(conn, room_id, user_id, Permission::ManageInvitations))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        if !is_permitted {
+            return Ok(RevokeInvitationCodeOutcome::NotPermitted);
+        }
+
+        let archived: bool = conn
+            .interact(move |conn| permissions::is_archived(conn, room_id))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        if archived {
+            return Ok(RevokeInvitationCodeOutcome::RoomArchived);
+        }
+
+        conn.interact(move |conn| {
+            conn.execute(
+                "UPDATE invitation_codes SET revoked_at = ?1 WHERE room_id = ?2 AND revoked_at IS NULL",
+                params![utils::now(), room_id],
+            )
+        })
+        .await
+        .map_err(|e| anyhow!("{e}"))??;
+
+        Ok(RevokeInvitationCodeOutcome::Success)
+    }
+}
@@ -0,0 +1,85 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::params;
+
+use crate::types::{NewMemberRole, RoomRole};
+use super::super::Database;
+use super::super::session;
+use super::super::permissions::{self, Permission};
+
+pub enum SetMemberRoleOutcome {
+    Success,
+    NotLoggedIn,
+    NotOwner,
+    NotMember,
+    CannotChangeOwner,
+    RoomArchived,
+}
+
+impl Database {
+    /// Attempts to promote or demote a member of a room using the logged in user's ID, a given room ID and member ID.
+    ///
+    /// Only the owner may change roles, and only between teacher and student. Ownership itself
+    /// cannot be granted or taken away here, and roles in archived rooms cannot change.
+    pub async fn set_member_role(
+        &self,
+        session_uuid: String,
+        room_id: i32,
+        member_id: i32,
+        new_role: NewMemberRole,
+    ) -> Result<SetMemberRoleOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| session::user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(SetMemberRoleOutcome::NotLoggedIn);
+        };
+
+        let is_owner: bool = conn
+            .interact(move |conn| permissions::has_permission(conn, room_id, user_id, Permission::ManageRoles))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        if !is_owner {
+            return Ok(SetMemberRoleOutcome::NotOwner);
+        }
+
+        let archived: bool = conn
+            .interact(move |conn| permissions::is_archived(conn, room_id))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        if archived {
+            return Ok(SetMemberRoleOutcome::RoomArchived);
+        }
+
+        let member_role = conn
+            .interact(move |conn| permissions::room_role(conn, room_id, i64::from(member_id)))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        match member_role {
+            None => return Ok(SetMemberRoleOutcome::NotMember),
+            Some(RoomRole::Owner) => return Ok(SetMemberRoleOutcome::CannotChangeOwner),
+            Some(RoomRole::Teacher | RoomRole::Student) => {}
+        }
+
+        if new_role.role == RoomRole::Owner {
+            return Ok(SetMemberRoleOutcome::CannotChangeOwner);
+        }
+
+        conn.interact(move |conn| {
+            conn.execute(
+                "UPDATE room_members SET role = ?1 WHERE room_id = ?2 AND user_id = ?3",
+                params![new_role.role, room_id, member_id],
+            )
+        })
+        .await
+        .map_err(|e| anyhow!("{e}"))??;
+
+        Ok(SetMemberRoleOutcome::Success)
+    }
+}
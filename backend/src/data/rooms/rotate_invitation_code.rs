@@ -0,0 +1,85 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::params;
+
+use crate::types::{InvitationCode, NewInvitationCode};
+use super::super::Database;
+use super::super::session;
+use super::super::permissions::{self, Permission};
+use super::super::utils;
+use super::invitation_code::insert_invitation_code;
+
+pub enum RotateInvitationCodeOutcome {
+    Success(InvitationCode),
+    NotLoggedIn,
+    NotPermitted,
+    InvalidSettings,
+    RoomArchived,
+}
+
+impl Database {
+    /// Attempts to replace the invitation code of a room using the logged in user's ID and a given room ID.
+    ///
+    /// Every code of the room that is still usable is revoked and a new one with the given
+    /// expiry (in seconds from now) and usage limit is created. Archived rooms get no new codes.
+    pub async fn rotate_invitation_code(
+        &self,
+        session_uuid: String,
+        room_id: i32,
+        settings: NewInvitationCode,
+    ) -> Result<RotateInvitationCodeOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| session::user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(RotateInvitationCodeOutcome::NotLoggedIn);
+        };
+
+        let is_permitted: bool = conn
+            .interact(move |conn| permissions::has_permission(conn, room_id, user_id, Permission::ManageInvitations))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        if !is_permitted {
+            return Ok(RotateInvitationCodeOutcome::NotPermitted);
+        }
+
+        let archived: bool = conn
+            .interact(move |conn| permissions::is_archived(conn, room_id))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        if archived {
+            return Ok(RotateInvitationCodeOutcome::RoomArchived);
+        }
+
+        if settings.expires_in.is_some_and(|secs| secs <= 0)
+            || settings.max_uses.is_some_and(|uses| uses <= 0)
+        {
+            return Ok(RotateInvitationCodeOutcome::InvalidSettings);
+        }
+
+        let code = conn
+            .interact(move |conn| {
+                let tx = conn.transaction()?;
+
+                tx.execute(
+                    "UPDATE invitation_codes SET revoked_at = ?1 WHERE room_id = ?2 AND revoked_at IS NULL",
+                    params![utils::now(), room_id],
+                )?;
+
+                let code = insert_invitation_code(&tx, room_id, &settings)?;
+
+                tx.commit()?;
+
+                Ok::<_, deadpool_sqlite::rusqlite::Error>(code)
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        Ok(RotateInvitationCodeOutcome::Success(code))
+    }
+}
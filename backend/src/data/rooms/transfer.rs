@@ -0,0 +1,75 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::{TransactionBehavior, params};
+
+use crate::types::{RoomRole, TransferOwnership};
+use super::super::Database;
+use super::super::session;
+use super::super::permissions::{self, Permission};
+
+pub enum TransferOwnershipOutcome {
+    Success,
+    NotLoggedIn,
+    NotOwner,
+    NotMember,
+    AlreadyOwner,
+}
+
+impl Database {
+    /// Attempts to hand a room over to another member using the logged in user's ID and a given room ID.
+    ///
+    /// The new owner must already be a member of the room. The previous owner stays in the room
+    /// as a teacher. Both role changes happen in a single transaction, so the room never ends up
+    /// with zero or two owners.
+    pub async fn transfer_ownership(
+        &self,
+        session_uuid: String,
+        room_id: i32,
+        transfer: TransferOwnership,
+    ) -> Result<TransferOwnershipOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| session::user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(TransferOwnershipOutcome::NotLoggedIn);
+        };
+
+        let new_owner_id = i64::from(transfer.user_id);
+
+        conn.interact(move |conn| -> Result<TransferOwnershipOutcome> {
+            let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+
+            if !permissions::has_permission(&tx, room_id, user_id, Permission::TransferOwnership)? {
+                return Ok(TransferOwnershipOutcome::NotOwner);
+            }
+
+            if new_owner_id == user_id {
+                return Ok(TransferOwnershipOutcome::AlreadyOwner);
+            }
+
+            if permissions::room_role(&tx, room_id, new_owner_id)?.is_none() {
+                return Ok(TransferOwnershipOutcome::NotMember);
+            }
+
+            // Demote first, the unique index allows only one owner per room at any time
+            tx.execute(
+                "UPDATE room_members SET role = ?1 WHERE room_id = ?2 AND user_id = ?3",
+                params![RoomRole::Teacher, room_id, user_id],
+            )?;
+
+            tx.execute(
+                "UPDATE room_members SET role = ?1 WHERE room_id = ?2 AND user_id = ?3",
+                params![RoomRole::Owner, room_id, new_owner_id],
+            )?;
+
+            tx.commit()?;
+
+            Ok(TransferOwnershipOutcome::Success)
+        })
+        .await
+        .map_err(|e| anyhow!("{e}"))?
+    }
+}
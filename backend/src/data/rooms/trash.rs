@@ -0,0 +1,195 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::{OptionalExtension, params};
+use std::path::Path;
+
+use crate::types::{RoomRole, TrashedRoom};
+use super::super::Database;
+use super::super::permissions::Permission;
+use super::super::session;
+use super::super::utils::now;
+
+pub enum ListTrashOutcome {
+    Success(Vec<TrashedRoom>),
+    NotLoggedIn,
+}
+
+pub enum RestoreRoomOutcome {
+    Success,
+    NotLoggedIn,
+    NotOwner,
+    NotInTrash,
+}
+
+impl Database {
+    /// Lists the deleted rooms the logged in user owns, most recently deleted first.
+    ///
+    /// `retention` is how long rooms stay in the trash, in seconds.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - a database connection cannot be acquired from the pool
+    /// - a database task fails to run or complete
+    /// - executing any SQL query fails
+    pub async fn list_trash(&self, session_uuid: String, retention: i64) -> Result<ListTrashOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| session::user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(ListTrashOutcome::NotLoggedIn);
+        };
+
+        let rooms = conn
+            .interact(move |conn| -> Result<Vec<TrashedRoom>> {
+                let mut stmt = conn.prepare(
+                    "
+                    SELECT r.id, r.name, r.description, r.deleted_at
+                    FROM rooms r
+                    JOIN room_members rm ON rm.room_id = r.id
+                    WHERE rm.user_id = ?1 AND rm.role = ?2 AND r.deleted_at IS NOT NULL
+                    ORDER BY r.deleted_at DESC, r.id DESC
+                    ",
+                )?;
+
+                let rooms_iter = stmt.query_map(params![user_id, RoomRole::Owner], |row| {
+                    let deleted_at: i64 = row.get(3)?;
+
+                    Ok(TrashedRoom {
+                        id: row.get(0)?,
+                        name: row.get(1)?,
+                        description: row.get(2)?,
+                        deleted_at,
+                        purge_at: deleted_at.saturating_add(retention),
+                    })
+                })?;
+
+                let mut rooms = Vec::new();
+                for room in rooms_iter {
+                    rooms.push(room?);
+                }
+
+                Ok(rooms)
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        Ok(ListTrashOutcome::Success(rooms))
+    }
+
+    /// Attempts to restore a deleted room using the logged in user's ID and a given room ID.
+    ///
+    /// Only the owner may restore a room, just like only they may delete it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - a database connection cannot be acquired from the pool
+    /// - a database task fails to run or complete
+    /// - executing any SQL query fails
+    pub async fn restore_room(&self, session_uuid: String, id: i32) -> Result<RestoreRoomOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| session::user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(RestoreRoomOutcome::NotLoggedIn);
+        };
+
+        let outcome = conn
+            .interact(move |conn| {
+                // `permissions::room_role` does not see deleted rooms, so the role is looked up here
+                let room: Option<(RoomRole, bool)> = conn
+                    .query_row(
+                        "
+                        SELECT rm.role, r.deleted_at IS NOT NULL
+                        FROM rooms r
+                        JOIN room_members rm ON rm.room_id = r.id
+                        WHERE r.id = ?1 AND rm.user_id = ?2
+                        ",
+                        params![id, user_id],
+                        |row| Ok((row.get(0)?, row.get(1)?)),
+                    )
+                    .optional()?;
+
+                let Some((role, deleted)) = room else {
+                    return Ok(RestoreRoomOutcome::NotOwner);
+                };
+
+                if !role.can(Permission::DeleteRoom) {
+                    return Ok(RestoreRoomOutcome::NotOwner);
+                }
+
+                if !deleted {
+                    return Ok(RestoreRoomOutcome::NotInTrash);
+                }
+
+                conn.execute("UPDATE rooms SET deleted_at = NULL WHERE id = ?1", params![id])?;
+
+                Ok::<_, deadpool_sqlite::rusqlite::Error>(RestoreRoomOutcome::Success)
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        Ok(outcome)
+    }
+
+    /// Removes rooms that have been in the trash for longer than `retention` seconds,
+    /// along with everything in them, and deletes their submitted files from `upload_dir`.
+    ///
+    /// Returns how many rooms were removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - a database connection cannot be acquired from the pool
+    /// - a database task fails to run or complete
+    /// - executing any SQL query fails
+    pub async fn purge_deleted_rooms(&self, retention: i64, upload_dir: &Path) -> Result<usize> {
+        let conn = self.pool.get().await?;
+
+        let cutoff = now().saturating_sub(retention);
+
+        let (purged, storage_names) = conn
+            .interact(move |conn| -> Result<(usize, Vec<String>)> {
+                let tx = conn.transaction()?;
+
+                let storage_names = tx
+                    .prepare(
+                        "
+                        SELECT f.storage_name
+                        FROM submission_files f
+                        JOIN submissions s ON s.id = f.submission_id
+                        JOIN assignments a ON a.id = s.assignment_id
+                        JOIN rooms r ON r.id = a.room_id
+                        WHERE r.deleted_at <= ?1
+                        ",
+                    )?
+                    .query_map(params![cutoff], |row| row.get(0))?
+                    .collect::<Result<Vec<String>, _>>()?;
+
+                // Everything else that belongs to the rooms is removed by its foreign keys
+                let purged = tx.execute("DELETE FROM rooms WHERE deleted_at <= ?1", params![cutoff])?;
+                tx.commit()?;
+
+                Ok((purged, storage_names))
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        // The rows are gone already, a file that cannot be removed is only wasted space
+        for storage_name in storage_names {
+            if let Err(e) = tokio::fs::remove_file(upload_dir.join(&storage_name)).await {
+                eprintln!("Failed to remove upload {storage_name}: {e}");
+            }
+        }
+
+        Ok(purged)
+    }
+}
@@ -0,0 +1,132 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::params;
+
+use crate::types::RoomUpdate;
+use super::super::Database;
+use super::super::session;
+use super::super::permissions::{self, Permission};
+
+/// Longest room name, in characters.
+const MAX_NAME_LENGTH: usize = 100;
+
+/// Longest room description, in characters.
+const MAX_DESCRIPTION_LENGTH: usize = 2000;
+
+/// Longest section or term, in characters.
+const MAX_LABEL_LENGTH: usize = 50;
+
+pub enum UpdateRoomOutcome {
+    Success,
+    NotLoggedIn,
+    NotOwner,
+    InvalidSettings,
+}
+
+/// Whether `color` is written as `#rrggbb`.
+fn is_hex_color(color: &str) -> bool {
+    color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Trims an optional text setting, an empty one means it should be removed.
+fn optional_text(value: Option<String>) -> Option<Option<String>> {
+    value.map(|value| Some(value.trim().to_string()).filter(|value| !value.is_empty()))
+}
+
+impl Database {
+    /// Changes a room's name, description and settings, leaving out fields that are `None`.
+    ///
+    /// Only the owner may change a room.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(UpdateRoomOutcome::Success)` if the room was updated.
+    /// - `Ok(UpdateRoomOutcome::NotLoggedIn)` if the session does not exist or has expired.
+    /// - `Ok(UpdateRoomOutcome::NotOwner)` if the user does not own the room, or it does not exist.
+    /// - `Ok(UpdateRoomOutcome::InvalidSettings)` if the name is empty, a text is too long
+    ///   or the colour is not `#rrggbb`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - a database connection cannot be acquired from the pool
+    /// - a database task fails to run or complete
+    /// - executing any SQL query fails
+    pub async fn update_room(
+        &self,
+        session_uuid: String,
+        room_id: i32,
+        update: RoomUpdate,
+    ) -> Result<UpdateRoomOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| session::user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(UpdateRoomOutcome::NotLoggedIn);
+        };
+
+        let is_owner: bool = conn
+            .interact(move |conn| permissions::has_permission(conn, room_id, user_id, Permission::ManageSettings))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        if !is_owner {
+            return Ok(UpdateRoomOutcome::NotOwner);
+        }
+
+        let name = update.name.map(|name| name.trim().to_string());
+        let description = update.description.map(|description| description.trim().to_string());
+        let color = optional_text(update.color);
+        let section = optional_text(update.section);
+        let term = optional_text(update.term);
+
+        let too_long = |value: Option<&String>, max: usize| value.is_some_and(|value| value.chars().count() > max);
+
+        if name.as_ref().is_some_and(String::is_empty)
+            || too_long(name.as_ref(), MAX_NAME_LENGTH)
+            || too_long(description.as_ref(), MAX_DESCRIPTION_LENGTH)
+            || too_long(section.as_ref().and_then(Option::as_ref), MAX_LABEL_LENGTH)
+            || too_long(term.as_ref().and_then(Option::as_ref), MAX_LABEL_LENGTH)
+            || color.as_ref().and_then(Option::as_ref).is_some_and(|color| !is_hex_color(color))
+        {
+            return Ok(UpdateRoomOutcome::InvalidSettings);
+        }
+
+        conn.interact(move |conn| {
+            // The flags tell a setting that is left alone apart from one that is removed
+            conn.execute(
+                "UPDATE rooms SET
+                    name = COALESCE(?1, name),
+                    description = COALESCE(?2, description),
+                    join_approval_required = COALESCE(?3, join_approval_required),
+                    visibility = COALESCE(?4, visibility),
+                    color = CASE WHEN ?5 THEN ?6 ELSE color END,
+                    section = CASE WHEN ?7 THEN ?8 ELSE section END,
+                    term = CASE WHEN ?9 THEN ?10 ELSE term END
+                WHERE id = ?11",
+                params![
+                    name,
+                    description,
+                    update.join_approval_required,
+                    update.visibility,
+                    color.is_some(),
+                    color.flatten(),
+                    section.is_some(),
+                    section.flatten(),
+                    term.is_some(),
+                    term.flatten(),
+                    room_id,
+                ],
+            )
+        })
+        .await
+        .map_err(|e| anyhow!("{e}"))??;
+
+        Ok(UpdateRoomOutcome::Success)
+    }
+}
@@ -0,0 +1,75 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::params;
+
+use crate::types::Session;
+use super::super::Database;
+use super::super::utils::now;
+
+pub enum ListSessionsOutcome {
+    Success(Vec<Session>),
+    NotLoggedIn,
+}
+
+impl Database {
+    /// Lists the active sessions of the logged in user, most recently used first.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(ListSessionsOutcome::Success(sessions))` with every session that has not expired.
+    /// - `Ok(ListSessionsOutcome::NotLoggedIn)` if the session does not exist or has expired.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - a database connection cannot be acquired from the pool
+    /// - a database task fails to run or complete
+    /// - executing any SQL query fails
+    pub async fn list_sessions(&self, session_uuid: String) -> Result<ListSessionsOutcome> {
+        let conn = self.pool.get().await?;
+
+        let current_uuid = session_uuid.clone();
+        let user_id = conn
+            .interact(move |conn| super::session_user_id(conn, &current_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(ListSessionsOutcome::NotLoggedIn);
+        };
+
+        let sessions = conn
+            .interact(move |conn| -> Result<Vec<Session>> {
+                let mut stmt = conn.prepare(
+                    "
+                    SELECT id, created_at, last_seen_at, expires_at, user_agent, ip, uuid = ?2
+                    FROM sessions
+                    WHERE user_id = ?1 AND expires_at > ?3
+                    ORDER BY last_seen_at DESC, id DESC
+                    ",
+                )?;
+
+                let sessions_iter = stmt.query_map(params![user_id, session_uuid, now()], |row| {
+                    Ok(Session {
+                        id: row.get(0)?,
+                        created_at: row.get(1)?,
+                        last_seen_at: row.get(2)?,
+                        expires_at: row.get(3)?,
+                        user_agent: row.get(4)?,
+                        ip: row.get(5)?,
+                        current: row.get(6)?,
+                    })
+                })?;
+
+                let mut sessions = Vec::new();
+                for session in sessions_iter {
+                    sessions.push(session?);
+                }
+
+                Ok(sessions)
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        Ok(ListSessionsOutcome::Success(sessions))
+    }
+}
@@ -1,27 +1,83 @@
 use anyhow::{anyhow, Result};
-use bcrypt::verify;
-use deadpool_sqlite::rusqlite::{OptionalExtension, params};
+use deadpool_sqlite::rusqlite::{self, Connection, OptionalExtension, params};
 use uuid::Uuid;
 
 use super::super::Database;
+use super::super::utils::{self, now};
+use super::{LOCKOUT_DURATION, LOCKOUT_THRESHOLD, LOGIN_CHALLENGE_TTL, SESSION_TTL};
 
 pub enum LoginOutcome {
     Success(String),
+    /// The password was right but the user has TOTP enabled, carries the challenge
+    /// to complete the login with
+    SecondFactorRequired(String),
+    /// The email is unknown or the password is wrong, deliberately not telling which
     InvalidCredentials,
-    UserDoesNotExist,
+    EmailNotVerified,
+    /// Too many wrong passwords in a row for the email, carries the seconds until it unlocks
+    AccountLocked(i64),
+}
+
+/// Creates a new session for a user and returns its UUID.
+pub(super) fn create_session(
+    conn: &Connection,
+    user_id: i64,
+    user_agent: Option<String>,
+    ip: Option<String>,
+) -> rusqlite::Result<String> {
+    let session_uuid = Uuid::new_v4().to_string();
+    let now = now();
+
+    conn.execute(
+        "INSERT INTO sessions (uuid, user_id, created_at, last_seen_at, expires_at, user_agent, ip)
+        VALUES (?1, ?2, ?3, ?3, ?4, ?5, ?6)",
+        params![session_uuid, user_id, now, now + SESSION_TTL, user_agent, ip],
+    )?;
+
+    Ok(session_uuid)
+}
+
+/// Starts a login that still needs a second factor and returns the challenge to complete it with.
+pub(super) fn create_login_challenge(conn: &Connection, user_id: i64) -> rusqlite::Result<String> {
+    let challenge = utils::generate_token();
+
+    conn.execute(
+        "INSERT INTO login_challenges (token_hash, user_id, expires_at) VALUES (?1, ?2, ?3)",
+        params![utils::hash_token(&challenge), user_id, now() + LOGIN_CHALLENGE_TTL],
+    )?;
+
+    Ok(challenge)
+}
+
+/// Whether a user has to enter a TOTP code to log in.
+pub(super) fn totp_enabled(conn: &Connection, user_id: i64) -> rusqlite::Result<bool> {
+    conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM totp_credentials WHERE user_id = ?1 AND enabled_at IS NOT NULL)",
+        params![user_id],
+        |row| row.get(0),
+    )
 }
 
 impl Database {
     /// Attempts to log in a user with the given email and password.
     ///
     /// If the credentials are valid, a new session is created and its UUID is returned.
+    /// The user agent and IP address of the client are stored with the session so the user
+    /// can tell their sessions apart later.
+    /// If `require_verified_email` is set, users who have not verified their email are turned away.
+    /// Users with TOTP enabled get a login challenge instead of a session, which
+    /// [`Database::complete_totp_login`] turns into a session.
     /// The function checks whether the user exists and whether the password matches the stored hash.
+    /// After too many wrong passwords in a row for an email the account is locked for a while,
+    /// during which the password is not accepted. This happens for unknown emails just the same.
     ///
     /// # Returns
     ///
     /// - `Ok(LoginOutcome::Success(uuid))` if the email exists and the password is correct.
-    /// - `Ok(LoginOutcome::InvalidCredentials)` if the email exists but the password is incorrect.
-    /// - `Ok(LoginOutcome::UserDoesNotExist)` if no user exists with the given email.
+    /// - `Ok(LoginOutcome::SecondFactorRequired(challenge))` if the password is correct and a TOTP code is needed.
+    /// - `Ok(LoginOutcome::InvalidCredentials)` if no user exists with the given email or the password is incorrect.
+    /// - `Ok(LoginOutcome::EmailNotVerified)` if the password is correct but the email must be verified first.
+    /// - `Ok(LoginOutcome::AccountLocked(seconds))` if the account is locked after too many wrong passwords.
     ///
     /// # Errors
     ///
@@ -29,43 +85,107 @@ impl Database {
     /// - a database connection cannot be acquired from the pool
     /// - a database task fails to run or complete
     /// - executing any SQL query fails
-    /// - password verification fails
-     pub async fn login_user(&self, email: String, password: String) -> Result<LoginOutcome> {
+     pub async fn login_user(
+        &self,
+        email: String,
+        password: String,
+        user_agent: Option<String>,
+        ip: Option<String>,
+        require_verified_email: bool,
+    ) -> Result<LoginOutcome> {
         let conn = self.pool.get().await?;
 
-        let user: Option<(i64, String)> = conn
+        let email = utils::normalize_email(&email);
+
+        // Failures are counted per email whether or not a user has it,
+        // so locking out does not tell which emails are registered either
+        let failure_key = email.clone();
+
+        let user: Option<(i64, String, bool)> = conn
             .interact(move |conn| {
                 conn.query_row(
-                    "SELECT id, password_hash FROM users WHERE email = ?1",
+                    "SELECT id, password_hash, email_verified_at IS NOT NULL FROM users WHERE email = ?1",
                     params![email],
-                    |row| Ok((row.get(0)?, row.get(1)?)),
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
                 )
                 .optional()
             })
             .await
             .map_err(|e| anyhow!("{e}"))??;
 
-        match user {
-            Some((user_id, hash)) => {
-                if verify(&password, &hash)? {
-                    let session_uuid = Uuid::new_v4().to_string();
-
-                    let session_uuid_clone = session_uuid.clone();
-                    conn.interact(move |conn| {
-                        conn.execute(
-                            "INSERT INTO sessions (uuid, user_id) VALUES (?1, ?2)",
-                            params![session_uuid_clone, user_id],
-                        )
-                    })
-                    .await
-                    .map_err(|e| anyhow!("{e}"))??;
-
-                    Ok(LoginOutcome::Success(session_uuid))
-                } else {
-                    Ok(LoginOutcome::InvalidCredentials)
-                }
+        let locked_until: Option<i64> = {
+            let failure_key = failure_key.clone();
+
+            conn.interact(move |conn| {
+                conn.query_row(
+                    "SELECT locked_until FROM login_failures WHERE email = ?1 AND locked_until > ?2",
+                    params![failure_key, now()],
+                    |row| row.get(0),
+                )
+                .optional()
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??
+        };
+
+        // Every answer takes as long as checking a password, so timing tells nothing either
+        if let Some(locked_until) = locked_until {
+            utils::verify_password(&password, utils::dummy_password_hash());
+            return Ok(LoginOutcome::AccountLocked(locked_until - now()));
+        }
+
+        let password_matches = match &user {
+            Some((_, hash, _)) => utils::verify_password(&password, hash),
+            None => {
+                utils::verify_password(&password, utils::dummy_password_hash());
+                false
             }
-            None => Ok(LoginOutcome::UserDoesNotExist),
+        };
+
+        let (Some((user_id, _, email_verified)), true) = (user, password_matches) else {
+            conn.interact(move |conn| {
+                let tx = conn.transaction()?;
+
+                tx.execute(
+                    "INSERT INTO login_failures (email, failures, last_failed_at) VALUES (?1, 1, ?2)
+                    ON CONFLICT(email) DO UPDATE SET failures = failures + 1, last_failed_at = excluded.last_failed_at",
+                    params![failure_key, now()],
+                )?;
+
+                // Locking starts the count over, so the next lock needs as many failures again
+                tx.execute(
+                    "UPDATE login_failures SET failures = 0, locked_until = ?1
+                    WHERE email = ?2 AND failures >= ?3",
+                    params![now() + LOCKOUT_DURATION, failure_key, LOCKOUT_THRESHOLD],
+                )?;
+
+                tx.commit()
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+            return Ok(LoginOutcome::InvalidCredentials);
+        };
+
+        conn.interact(move |conn| {
+            conn.execute("DELETE FROM login_failures WHERE email = ?1", params![failure_key])
+        })
+        .await
+        .map_err(|e| anyhow!("{e}"))??;
+
+        if require_verified_email && !email_verified {
+            return Ok(LoginOutcome::EmailNotVerified);
         }
+
+        conn.interact(move |conn| {
+            if totp_enabled(conn, user_id)? {
+                return Ok(LoginOutcome::SecondFactorRequired(create_login_challenge(conn, user_id)?));
+            }
+
+            Ok::<_, rusqlite::Error>(LoginOutcome::Success(create_session(conn, user_id, user_agent, ip)?))
+        })
+        .await
+        .map_err(|e| anyhow!("{e}"))?
+        .map_err(Into::into)
     }
 }
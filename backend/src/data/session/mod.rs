@@ -1,7 +1,44 @@
+/// How long a session stays valid after it was last used, in seconds.
+pub const SESSION_TTL: i64 = 30 * 24 * 60 * 60;
+
+/// Every API token starts with this, which tells them apart from session UUIDs.
+pub const API_TOKEN_PREFIX: &str = "crate_";
+
+/// How long a user has to enter their second factor after their password, in seconds.
+const LOGIN_CHALLENGE_TTL: i64 = 5 * 60;
+
+/// How many wrong second factors a login challenge survives.
+const LOGIN_CHALLENGE_ATTEMPTS: i64 = 5;
+
+/// How long a user has to come back from the identity provider, in seconds.
+const OIDC_LOGIN_TTL: i64 = 10 * 60;
+
+/// How many wrong passwords in a row lock an account.
+const LOCKOUT_THRESHOLD: i64 = 5;
+
+/// How long a locked account stays locked, in seconds.
+const LOCKOUT_DURATION: i64 = 15 * 60;
+
+mod list;
+pub use list::ListSessionsOutcome;
+
 mod login;
 pub use login::LoginOutcome;
 
 mod logout;
 pub use logout::LogoutOutcome;
 
+mod oidc;
+pub use oidc::OidcLoginOutcome;
+
+mod purge;
+
+mod revoke;
+pub use revoke::RevokeOtherSessionsOutcome;
+pub use revoke::RevokeSessionOutcome;
+
+mod totp_login;
+pub use totp_login::CompleteTotpLoginOutcome;
+
 mod verify;
+pub use verify::{session_user_id, user_id};
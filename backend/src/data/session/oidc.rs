@@ -0,0 +1,217 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::{OptionalExtension, TransactionBehavior, params};
+
+use crate::types::OidcIdentity;
+use super::super::Database;
+use super::super::utils::{self, now};
+use super::login::{create_login_challenge, create_session, totp_enabled};
+use super::OIDC_LOGIN_TTL;
+
+pub enum OidcLoginOutcome {
+    Success(String),
+    /// The user has TOTP enabled, carries the challenge to complete the login with
+    SecondFactorRequired(String),
+    EmailNotVerified,
+    /// A user with this email exists, but the provider does not vouch for the email,
+    /// so the accounts are not linked
+    EmailInUse,
+}
+
+impl Database {
+    /// Remembers a single sign-on login until the user comes back from the identity provider.
+    ///
+    /// Only a hash of `state` is stored, the PKCE verifier and nonce are stored as they are
+    /// since they have to be handed to the provider and checked against the ID token.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - a database connection cannot be acquired from the pool
+    /// - a database task fails to run or complete
+    /// - executing the SQL query fails
+    pub async fn start_oidc_login(&self, state: String, pkce_verifier: String, nonce: String) -> Result<()> {
+        let conn = self.pool.get().await?;
+
+        let state_hash = utils::hash_token(&state);
+
+        conn.interact(move |conn| {
+            conn.execute(
+                "INSERT INTO oidc_logins (state_hash, pkce_verifier, nonce, expires_at) VALUES (?1, ?2, ?3, ?4)",
+                params![state_hash, pkce_verifier, nonce, now() + OIDC_LOGIN_TTL],
+            )
+        })
+        .await
+        .map_err(|e| anyhow!("{e}"))??;
+
+        Ok(())
+    }
+
+    /// Uses up a login started with [`Database::start_oidc_login`], returning its PKCE verifier
+    /// and nonce, or `None` if `state` is unknown, expired or was already used.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - a database connection cannot be acquired from the pool
+    /// - a database task fails to run or complete
+    /// - executing the SQL query fails
+    pub async fn take_oidc_login(&self, state: String) -> Result<Option<(String, String)>> {
+        let conn = self.pool.get().await?;
+
+        let state_hash = utils::hash_token(&state);
+
+        let login = conn
+            .interact(move |conn| {
+                conn.query_row(
+                    "DELETE FROM oidc_logins WHERE state_hash = ?1 AND expires_at > ?2
+                    RETURNING pkce_verifier, nonce",
+                    params![state_hash, now()],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        Ok(login)
+    }
+
+    /// Logs in the user an identity provider vouched for, creating a session like
+    /// [`Database::login_user`] does.
+    ///
+    /// A known identity logs in the user it is linked to. An unknown one is linked to the user
+    /// with the same email if the provider verified that email, and otherwise gets a new user.
+    /// New users get a random password, which they can replace through a password reset.
+    ///
+    /// Anyone could have registered a user that never verified its email, so linking to one takes
+    /// it over: its password is replaced with a random one and its sessions, API tokens and TOTP
+    /// are removed. Users who turned on TOTP here still have to enter a code, like with
+    /// [`Database::login_user`], since the provider's second factor is not theirs to rely on.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(OidcLoginOutcome::Success(session_uuid))` if a session was created.
+    /// - `Ok(OidcLoginOutcome::SecondFactorRequired(challenge))` if a TOTP code is needed.
+    /// - `Ok(OidcLoginOutcome::EmailNotVerified)` if the user's email must be verified first.
+    /// - `Ok(OidcLoginOutcome::EmailInUse)` if the email belongs to a user the identity cannot be linked to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - a database connection cannot be acquired from the pool
+    /// - a database task fails to run or complete
+    /// - executing any SQL query fails
+    /// - password hashing fails
+    pub async fn oidc_login(
+        &self,
+        identity: OidcIdentity,
+        user_agent: Option<String>,
+        ip: Option<String>,
+        require_verified_email: bool,
+    ) -> Result<OidcLoginOutcome> {
+        let conn = self.pool.get().await?;
+
+        // Only needed for new or taken over users, but hashing inside the transaction would hold the write lock
+        let password_hash = utils::hash_password(&utils::generate_token())?;
+
+        let email = utils::normalize_email(&identity.email);
+
+        conn.interact(move |conn| -> Result<OidcLoginOutcome> {
+            let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+            let now = now();
+            let verified_at = identity.email_verified.then_some(now);
+
+            let linked_user: Option<i64> = tx
+                .query_row(
+                    "SELECT user_id FROM oidc_identities WHERE issuer = ?1 AND subject = ?2",
+                    params![identity.issuer, identity.subject],
+                    |row| row.get(0),
+                )
+                .optional()?;
+
+            let user_id = if let Some(user_id) = linked_user {
+                user_id
+            } else {
+                let existing_user: Option<(i64, bool)> = tx
+                    .query_row(
+                        "SELECT id, email_verified_at IS NOT NULL FROM users WHERE email = ?1",
+                        params![email],
+                        |row| Ok((row.get(0)?, row.get(1)?)),
+                    )
+                    .optional()?;
+
+                let user_id = match existing_user {
+                    // Anyone can claim any email at some providers, so only a verified one links
+                    Some(_) if !identity.email_verified => return Ok(OidcLoginOutcome::EmailInUse),
+                    Some((user_id, true)) => user_id,
+                    // Whoever registered the user never proved the email was theirs, so nothing
+                    // they set up may keep working for the actual owner of the email
+                    Some((user_id, false)) => {
+                        tx.execute(
+                            "UPDATE users SET password_hash = ?1 WHERE id = ?2",
+                            params![password_hash, user_id],
+                        )?;
+                        tx.execute("DELETE FROM sessions WHERE user_id = ?1", params![user_id])?;
+                        tx.execute("DELETE FROM api_tokens WHERE user_id = ?1", params![user_id])?;
+                        tx.execute("DELETE FROM login_challenges WHERE user_id = ?1", params![user_id])?;
+                        tx.execute("DELETE FROM totp_credentials WHERE user_id = ?1", params![user_id])?;
+                        tx.execute("DELETE FROM recovery_codes WHERE user_id = ?1", params![user_id])?;
+
+                        user_id
+                    }
+                    None => {
+                        tx.execute(
+                            "INSERT INTO users (email, name, surname, password_hash, email_verified_at)
+                            VALUES (?1, ?2, ?3, ?4, ?5)",
+                            params![email, identity.name, identity.surname, password_hash, verified_at],
+                        )?;
+                        tx.last_insert_rowid()
+                    }
+                };
+
+                tx.execute(
+                    "INSERT INTO oidc_identities (issuer, subject, user_id, created_at) VALUES (?1, ?2, ?3, ?4)",
+                    params![identity.issuer, identity.subject, user_id, now],
+                )?;
+
+                user_id
+            };
+
+            // The provider vouching for the user's current email counts as verifying it
+            if identity.email_verified {
+                tx.execute(
+                    "UPDATE users SET email_verified_at = ?1
+                    WHERE id = ?2 AND email = ?3 AND email_verified_at IS NULL",
+                    params![now, user_id, email],
+                )?;
+            }
+
+            let email_verified: bool = tx.query_row(
+                "SELECT email_verified_at IS NOT NULL FROM users WHERE id = ?1",
+                params![user_id],
+                |row| row.get(0),
+            )?;
+
+            if require_verified_email && !email_verified {
+                // The link is kept, so verifying the email later is enough
+                tx.commit()?;
+                return Ok(OidcLoginOutcome::EmailNotVerified);
+            }
+
+            if totp_enabled(&tx, user_id)? {
+                let challenge = create_login_challenge(&tx, user_id)?;
+                tx.commit()?;
+
+                return Ok(OidcLoginOutcome::SecondFactorRequired(challenge));
+            }
+
+            let session_uuid = create_session(&tx, user_id, user_agent, ip)?;
+
+            tx.commit()?;
+
+            Ok(OidcLoginOutcome::Success(session_uuid))
+        })
+        .await
+        .map_err(|e| anyhow!("{e}"))?
+    }
+}
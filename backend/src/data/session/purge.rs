@@ -0,0 +1,49 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::params;
+
+use super::super::Database;
+use super::super::utils::now;
+use super::LOCKOUT_DURATION;
+
+impl Database {
+    /// Deletes every session, login challenge and single sign-on login that has expired, as well as
+    /// wrong password counts that no longer matter, and returns how many sessions were removed.
+    ///
+    /// Expired rows are already rejected on lookup, this only keeps the tables from growing.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - a database connection cannot be acquired from the pool
+    /// - a database task fails to run or complete
+    /// - executing the SQL query fails
+    pub async fn purge_expired_sessions(&self) -> Result<usize> {
+        let conn = self.pool.get().await?;
+
+        let purged = conn
+            .interact(move |conn| {
+                conn.execute(
+                    "DELETE FROM login_challenges WHERE expires_at <= ?1",
+                    params![now()],
+                )?;
+                conn.execute(
+                    "DELETE FROM oidc_logins WHERE expires_at <= ?1",
+                    params![now()],
+                )?;
+                // Any email can be counted, so old counts are dropped once they could not lock anymore
+                conn.execute(
+                    "DELETE FROM login_failures
+                    WHERE last_failed_at <= ?1 AND (locked_until IS NULL OR locked_until <= ?2)",
+                    params![now() - LOCKOUT_DURATION, now()],
+                )?;
+                conn.execute(
+                    "DELETE FROM sessions WHERE expires_at <= ?1",
+                    params![now()],
+                )
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        Ok(purged)
+    }
+}
@@ -0,0 +1,99 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::params;
+
+use super::super::Database;
+
+pub enum RevokeSessionOutcome {
+    Success,
+    NotLoggedIn,
+    SessionNotFound,
+}
+
+pub enum RevokeOtherSessionsOutcome {
+    Success(usize),
+    NotLoggedIn,
+}
+
+impl Database {
+    /// Logs out one of the logged in user's sessions by its ID, which may be the current one.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(RevokeSessionOutcome::Success)` if the session was found and deleted.
+    /// - `Ok(RevokeSessionOutcome::NotLoggedIn)` if the calling session does not exist or has expired.
+    /// - `Ok(RevokeSessionOutcome::SessionNotFound)` if the user has no session with the given ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - a database connection cannot be acquired from the pool
+    /// - a database task fails to run or complete
+    /// - executing any SQL query fails
+    pub async fn revoke_session(&self, session_uuid: String, id: i64) -> Result<RevokeSessionOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| super::session_user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(RevokeSessionOutcome::NotLoggedIn);
+        };
+
+        let deleted = conn
+            .interact(move |conn| {
+                conn.execute(
+                    "DELETE FROM sessions WHERE id = ?1 AND user_id = ?2",
+                    params![id, user_id],
+                )
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        if deleted > 0 {
+            Ok(RevokeSessionOutcome::Success)
+        } else {
+            Ok(RevokeSessionOutcome::SessionNotFound)
+        }
+    }
+
+    /// Logs out every session of the logged in user except the one making the request.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(RevokeOtherSessionsOutcome::Success(count))` with the number of sessions deleted.
+    /// - `Ok(RevokeOtherSessionsOutcome::NotLoggedIn)` if the calling session does not exist or has expired.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - a database connection cannot be acquired from the pool
+    /// - a database task fails to run or complete
+    /// - executing any SQL query fails
+    pub async fn revoke_other_sessions(&self, session_uuid: String) -> Result<RevokeOtherSessionsOutcome> {
+        let conn = self.pool.get().await?;
+
+        let current_uuid = session_uuid.clone();
+        let user_id = conn
+            .interact(move |conn| super::session_user_id(conn, &current_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(RevokeOtherSessionsOutcome::NotLoggedIn);
+        };
+
+        let deleted = conn
+            .interact(move |conn| {
+                conn.execute(
+                    "DELETE FROM sessions WHERE user_id = ?1 AND uuid != ?2",
+                    params![user_id, session_uuid],
+                )
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        Ok(RevokeOtherSessionsOutcome::Success(deleted))
+    }
+}
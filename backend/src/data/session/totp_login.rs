@@ -0,0 +1,85 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::{OptionalExtension, TransactionBehavior, params};
+
+use super::super::Database;
+use super::super::totp;
+use super::super::utils::{self, now};
+use super::login::create_session;
+use super::LOGIN_CHALLENGE_ATTEMPTS;
+
+pub enum CompleteTotpLoginOutcome {
+    Success(String),
+    InvalidChallenge,
+    InvalidCode,
+}
+
+impl Database {
+    /// Completes a login that needs a second factor, using the challenge from
+    /// [`Database::login_user`] and either a TOTP code or a recovery code.
+    ///
+    /// A challenge is discarded once it is used or has seen too many wrong codes.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(CompleteTotpLoginOutcome::Success(uuid))` with the new session if the code is correct.
+    /// - `Ok(CompleteTotpLoginOutcome::InvalidChallenge)` if the challenge does not exist or has expired.
+    /// - `Ok(CompleteTotpLoginOutcome::InvalidCode)` if the code is wrong or was already used.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - a database connection cannot be acquired from the pool
+    /// - a database task fails to run or complete
+    /// - executing any SQL query fails
+    pub async fn complete_totp_login(
+        &self,
+        challenge: String,
+        code: String,
+        user_agent: Option<String>,
+        ip: Option<String>,
+    ) -> Result<CompleteTotpLoginOutcome> {
+        let conn = self.pool.get().await?;
+
+        let challenge_hash = utils::hash_token(&challenge);
+
+        conn.interact(move |conn| -> Result<CompleteTotpLoginOutcome> {
+            let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+
+            let pending: Option<(i64, i64)> = tx
+                .query_row(
+                    "SELECT user_id, attempts FROM login_challenges WHERE token_hash = ?1 AND expires_at > ?2",
+                    params![challenge_hash, now()],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()?;
+
+            let Some((user_id, attempts)) = pending else {
+                return Ok(CompleteTotpLoginOutcome::InvalidChallenge);
+            };
+
+            if !totp::verify_second_factor(&tx, user_id, &code)? {
+                if attempts + 1 >= LOGIN_CHALLENGE_ATTEMPTS {
+                    tx.execute("DELETE FROM login_challenges WHERE token_hash = ?1", params![challenge_hash])?;
+                } else {
+                    tx.execute(
+                        "UPDATE login_challenges SET attempts = attempts + 1 WHERE token_hash = ?1",
+                        params![challenge_hash],
+                    )?;
+                }
+
+                tx.commit()?;
+
+                return Ok(CompleteTotpLoginOutcome::InvalidCode);
+            }
+
+            tx.execute("DELETE FROM login_challenges WHERE token_hash = ?1", params![challenge_hash])?;
+            let session_uuid = create_session(&tx, user_id, user_agent, ip)?;
+
+            tx.commit()?;
+
+            Ok(CompleteTotpLoginOutcome::Success(session_uuid))
+        })
+        .await
+        .map_err(|e| anyhow!("{e}"))?
+    }
+}
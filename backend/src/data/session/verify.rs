@@ -1,12 +1,56 @@
 use anyhow::{anyhow, Result};
-use deadpool_sqlite::rusqlite::{OptionalExtension, params};
+use deadpool_sqlite::rusqlite::{self, Connection, OptionalExtension, params};
 
 use super::super::Database;
+use super::super::utils::{self, now};
+use super::{API_TOKEN_PREFIX, SESSION_TTL};
+
+/// Looks up the user a session or API token belongs to, `None` if it does not exist or has expired.
+///
+/// API tokens are told apart from session UUIDs by [`API_TOKEN_PREFIX`], the time they were
+/// last used is recorded. Sessions are looked up like in [`session_user_id`].
+///
+/// Meant to be called from inside `interact`, e.g.
+/// `conn.interact(move |conn| session::user_id(conn, &session_uuid))`.
+pub fn user_id(conn: &Connection, credential: &str) -> rusqlite::Result<Option<i64>> {
+    if !credential.starts_with(API_TOKEN_PREFIX) {
+        return session_user_id(conn, credential);
+    }
+
+    let now = now();
+
+    conn.query_row(
+        "UPDATE api_tokens SET last_used_at = ?2
+        WHERE token_hash = ?1 AND expires_at > ?2
+        RETURNING user_id",
+        params![utils::hash_token(credential), now],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+/// Looks up the user a session belongs to, `None` if it does not exist or has expired.
+///
+/// Every successful lookup slides the expiry of the session forward by [`SESSION_TTL`].
+/// Unlike [`user_id`] this never accepts an API token, it guards what only a logged in
+/// user may do, like managing sessions, API tokens or the account's credentials.
+pub fn session_user_id(conn: &Connection, session_uuid: &str) -> rusqlite::Result<Option<i64>> {
+    let now = now();
+
+    conn.query_row(
+        "UPDATE sessions SET last_seen_at = ?2, expires_at = ?3
+        WHERE uuid = ?1 AND expires_at > ?2
+        RETURNING user_id",
+        params![session_uuid, now, now + SESSION_TTL],
+        |row| row.get(0),
+    )
+    .optional()
+}
 
 impl Database {
-    /// Checks whether a session with the given UUID exists.
+    /// Checks whether a session with the given UUID exists and has not expired.
     ///
-    /// Returns `true` if the session exists, `false` otherwise.
+    /// Returns `true` if the session is valid, `false` otherwise.
     ///
     /// # Errors
     ///
@@ -17,18 +61,32 @@ impl Database {
     pub async fn verify_session(&self, session_uuid: String) -> Result<bool> {
         let conn = self.pool.get().await?;
 
-        let exists: Option<i64> = conn
-            .interact(move |conn| {
-                conn.query_row(
-                    "SELECT user_id FROM sessions WHERE uuid = ?1",
-                    params![session_uuid],
-                    |row| row.get(0),
-                )
-                .optional()
-            })
+        let user_id = conn
+            .interact(move |conn| user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        Ok(user_id.is_some())
+    }
+
+    /// Looks up the user a session or API token belongs to, like [`user_id`].
+    ///
+    /// Returns `None` if the credential does not exist or has expired.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - a database connection cannot be acquired from the pool
+    /// - a database task fails to run or complete
+    /// - executing the SQL query fails
+    pub async fn credential_user_id(&self, credential: String) -> Result<Option<i64>> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| user_id(conn, &credential))
             .await
             .map_err(|e| anyhow!("{e}"))??;
 
-        Ok(exists.is_some())
+        Ok(user_id)
     }
 }
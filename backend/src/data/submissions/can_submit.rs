@@ -0,0 +1,89 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::{self, Connection, OptionalExtension, params};
+
+use super::super::Database;
+use super::super::session;
+use super::super::permissions;
+
+pub enum CanSubmitOutcome {
+    Allowed,
+    NotLoggedIn,
+    AssignmentNotFound,
+    AlreadySubmitted,
+    RoomArchived,
+}
+
+impl Database {
+    /// Checks whether the logged in user may submit files for a given assignment.
+    ///
+    /// Lets the upload route turn a request away before reading its body. The same rules are
+    /// checked again by [`Database::create_submission`].
+    pub async fn can_submit(&self, session_uuid: String, assignment_id: i32) -> Result<CanSubmitOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| session::user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(CanSubmitOutcome::NotLoggedIn);
+        };
+
+        let outcome = conn
+            .interact(move |conn| submission_check(conn, assignment_id, user_id))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        Ok(outcome)
+    }
+}
+
+/// Checks whether a user may submit for an assignment.
+///
+/// Assignments in rooms the user is not a member of are reported as
+/// [`CanSubmitOutcome::AssignmentNotFound`]. Archived rooms take no new submissions and each
+/// user may submit once per assignment.
+pub(super) fn submission_check(
+    conn: &Connection,
+    assignment_id: i32,
+    user_id: i64,
+) -> rusqlite::Result<CanSubmitOutcome> {
+    // Whether the assignment's room is archived, `None` if the user cannot see the assignment
+    let archived: Option<bool> = conn
+        .query_row(
+            &format!(
+                "
+                SELECT r.archived_at IS NOT NULL
+                FROM assignments a
+                JOIN room_members rm ON rm.room_id = a.room_id
+                JOIN rooms r ON r.id = a.room_id
+                WHERE a.id = ?1 AND rm.user_id = ?2 AND r.deleted_at IS NULL AND {}
+                ",
+                permissions::NOT_HIDDEN,
+            ),
+            params![assignment_id, user_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    let Some(archived) = archived else {
+        return Ok(CanSubmitOutcome::AssignmentNotFound);
+    };
+
+    if archived {
+        return Ok(CanSubmitOutcome::RoomArchived);
+    }
+
+    let already_submitted: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM submissions WHERE assignment_id = ?1 AND user_id = ?2)",
+        params![assignment_id, user_id],
+        |row| row.get(0),
+    )?;
+
+    if already_submitted {
+        return Ok(CanSubmitOutcome::AlreadySubmitted);
+    }
+
+    Ok(CanSubmitOutcome::Allowed)
+}
@@ -0,0 +1,145 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::params;
+use uuid::Uuid;
+
+use crate::types::NewSubmissionFile;
+use super::can_submit::{submission_check, CanSubmitOutcome};
+use super::super::Database;
+use super::super::session;
+use super::super::utils;
+
+pub enum CreateSubmissionOutcome {
+    Success(i32),
+    NotLoggedIn,
+    AssignmentNotFound,
+    AlreadySubmitted,
+    RoomArchived,
+}
+
+struct PendingFile {
+    storage_name: String,
+    file_name: String,
+    content_type: String,
+    size: i64,
+}
+
+impl Database {
+    /// Attempts to submit files for an assignment using the logged in user's ID and a given assignment ID.
+    ///
+    /// The files are written to `upload_dir` under randomly generated names and their metadata
+    /// is recorded in the `submission_files` table. Who may submit is decided as in
+    /// [`Database::can_submit`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - a database connection cannot be acquired from the pool
+    /// - a database task fails to run or complete
+    /// - the required SQL queries fail to execute
+    /// - the files cannot be written to `upload_dir`
+    pub async fn create_submission(
+        &self,
+        session_uuid: String,
+        assignment_id: i32,
+        files: Vec<NewSubmissionFile>,
+        upload_dir: &Path,
+    ) -> Result<CreateSubmissionOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| session::user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(CreateSubmissionOutcome::NotLoggedIn);
+        };
+
+        let check = conn
+            .interact(move |conn| submission_check(conn, assignment_id, user_id))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        match check {
+            CanSubmitOutcome::Allowed => {}
+            CanSubmitOutcome::NotLoggedIn => return Ok(CreateSubmissionOutcome::NotLoggedIn),
+            CanSubmitOutcome::AssignmentNotFound => return Ok(CreateSubmissionOutcome::AssignmentNotFound),
+            CanSubmitOutcome::AlreadySubmitted => return Ok(CreateSubmissionOutcome::AlreadySubmitted),
+            CanSubmitOutcome::RoomArchived => return Ok(CreateSubmissionOutcome::RoomArchived),
+        }
+
+        tokio::fs::create_dir_all(upload_dir).await?;
+
+        let mut pending = Vec::with_capacity(files.len());
+        for file in files {
+            let storage_name = Uuid::new_v4().to_string();
+
+            if let Err(e) = tokio::fs::write(upload_dir.join(&storage_name), &file.data).await {
+                remove_files(upload_dir, &pending).await;
+                return Err(e.into());
+            }
+
+            pending.push(PendingFile {
+                storage_name,
+                file_name: file.file_name,
+                content_type: file.content_type,
+                size: i64::try_from(file.data.len())?,
+            });
+        }
+
+        let (pending, result) = conn
+            .interact(move |conn| {
+                let result = (|| -> Result<i32> {
+                    let tx = conn.transaction()?;
+
+                    tx.execute(
+                        "INSERT INTO submissions (assignment_id, user_id, submitted_at) VALUES (?1, ?2, ?3)",
+                        params![assignment_id, user_id, utils::now()],
+                    )?;
+
+                    let submission_id = tx.last_insert_rowid();
+
+                    for file in &pending {
+                        tx.execute(
+                            "INSERT INTO submission_files (submission_id, file_name, content_type, size, storage_name)
+                            VALUES (?1, ?2, ?3, ?4, ?5)",
+                            params![
+                                submission_id,
+                                file.file_name,
+                                file.content_type,
+                                file.size,
+                                file.storage_name,
+                            ],
+                        )?;
+                    }
+
+                    tx.commit()?;
+
+                    Ok(i32::try_from(submission_id)?)
+                })();
+
+                (pending, result)
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))?;
+
+        match result {
+            Ok(submission_id) => Ok(CreateSubmissionOutcome::Success(submission_id)),
+            Err(e) => {
+                remove_files(upload_dir, &pending).await;
+                Err(e)
+            }
+        }
+    }
+}
+
+/// Best effort cleanup of files written for a submission that could not be recorded.
+async fn remove_files(upload_dir: &Path, files: &[PendingFile]) {
+    for file in files {
+        if let Err(e) = tokio::fs::remove_file(upload_dir.join(&file.storage_name)).await {
+            eprintln!("Failed to remove upload {}: {e}", file.storage_name);
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::{OptionalExtension, params};
+
+use crate::types::StoredFile;
+use super::super::Database;
+use super::super::session;
+use super::super::permissions::{self, Permission};
+
+pub enum GetSubmissionFileOutcome {
+    Success(StoredFile),
+    NotLoggedIn,
+    NotFound,
+}
+
+impl Database {
+    /// Attempts to locate a file of a submission using the logged in user's ID, a given submission ID and file ID.
+    ///
+    /// Follows the same visibility rules as [`Database::get_submission`].
+    pub async fn get_submission_file(
+        &self,
+        session_uuid: String,
+        submission_id: i32,
+        file_id: i32,
+        upload_dir: &Path,
+    ) -> Result<GetSubmissionFileOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| session::user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(GetSubmissionFileOutcome::NotLoggedIn);
+        };
+
+        let file: Option<(String, String, String)> = conn
+            .interact(move |conn| -> Result<_> {
+                let file: Option<(i32, i64, String, String, String)> = conn
+                    .query_row(
+                        "
+                        SELECT a.room_id, s.user_id, f.file_name, f.content_type, f.storage_name
+                        FROM submission_files f
+                        JOIN submissions s ON s.id = f.submission_id
+                        JOIN assignments a ON a.id = s.assignment_id
+                        JOIN rooms r ON r.id = a.room_id
+                        WHERE f.id = ?1 AND s.id = ?2 AND r.deleted_at IS NULL
+                        ",
+                        params![file_id, submission_id],
+                        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+                    )
+                    .optional()?;
+
+                let Some((room_id, submitter_id, file_name, content_type, storage_name)) = file else {
+                    return Ok(None);
+                };
+
+                if permissions::is_hidden_from(conn, room_id, user_id)? {
+                    return Ok(None);
+                }
+
+                if submitter_id != user_id
+                    && !permissions::has_permission(conn, room_id, user_id, Permission::ViewAllSubmissions)?
+                {
+                    return Ok(None);
+                }
+
+                Ok(Some((file_name, content_type, storage_name)))
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some((file_name, content_type, storage_name)) = file else {
+            return Ok(GetSubmissionFileOutcome::NotFound);
+        };
+
+        Ok(GetSubmissionFileOutcome::Success(StoredFile {
+            file_name,
+            content_type,
+            path: upload_dir.join(storage_name),
+        }))
+    }
+}
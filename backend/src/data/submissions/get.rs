@@ -0,0 +1,120 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::{Connection, OptionalExtension, params};
+
+use crate::types::{Submission, SubmissionFile};
+use super::super::Database;
+use super::super::session;
+use super::super::permissions::{self, Permission};
+
+pub enum GetSubmissionOutcome {
+    Success(Submission),
+    NotLoggedIn,
+    NotFound,
+}
+
+impl Database {
+    /// Attempts to get a submission using the logged in user's ID and a given submission ID.
+    ///
+    /// A submission is only visible to the user who submitted it and to the owners and teachers
+    /// of the room the assignment belongs to. Anyone else gets [`GetSubmissionOutcome::NotFound`].
+    pub async fn get_submission(
+        &self,
+        session_uuid: String,
+        submission_id: i32,
+    ) -> Result<GetSubmissionOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| session::user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(GetSubmissionOutcome::NotLoggedIn);
+        };
+
+        let submission: Option<Submission> = conn
+            .interact(move |conn| -> Result<Option<Submission>> {
+                let submission = conn
+                    .query_row(
+                        "
+                        SELECT a.room_id, s.id, s.assignment_id, s.user_id, s.submitted_at
+                        FROM submissions s
+                        JOIN assignments a ON a.id = s.assignment_id
+                        JOIN rooms r ON r.id = a.room_id
+                        WHERE s.id = ?1 AND r.deleted_at IS NULL
+                        ",
+                        params![submission_id],
+                        |row| {
+                            Ok((
+                                row.get::<_, i32>(0)?,
+                                Submission {
+                                    id: row.get(1)?,
+                                    assignment_id: row.get(2)?,
+                                    user_id: row.get(3)?,
+                                    submitted_at: row.get(4)?,
+                                    files: Vec::new(),
+                                },
+                            ))
+                        },
+                    )
+                    .optional()?;
+
+                let Some((room_id, mut submission)) = submission else {
+                    return Ok(None);
+                };
+
+                if permissions::is_hidden_from(conn, room_id, user_id)? {
+                    return Ok(None);
+                }
+
+                if i64::from(submission.user_id) != user_id
+                    && !permissions::has_permission(conn, room_id, user_id, Permission::ViewAllSubmissions)?
+                {
+                    return Ok(None);
+                }
+
+                submission.files = submission_files(conn, submission.id)?;
+
+                Ok(Some(submission))
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        match submission {
+            Some(submission) => Ok(GetSubmissionOutcome::Success(submission)),
+            None => Ok(GetSubmissionOutcome::NotFound),
+        }
+    }
+}
+
+/// Loads the metadata of all files belonging to a submission.
+pub(super) fn submission_files(
+    conn: &Connection,
+    submission_id: i32,
+) -> Result<Vec<SubmissionFile>> {
+    let mut stmt = conn.prepare(
+        "
+        SELECT id, file_name, content_type, size
+        FROM submission_files
+        WHERE submission_id = ?1
+        ORDER BY id
+        ",
+    )?;
+
+    let files_iter = stmt.query_map(params![submission_id], |row| {
+        Ok(SubmissionFile {
+            id: row.get(0)?,
+            file_name: row.get(1)?,
+            content_type: row.get(2)?,
+            size: row.get(3)?,
+        })
+    })?;
+
+    let mut files = Vec::new();
+    for file in files_iter {
+        files.push(file?);
+    }
+
+    Ok(files)
+}
@@ -0,0 +1,97 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::{OptionalExtension, params};
+
+use crate::types::Submission;
+use super::super::Database;
+use super::super::session;
+use super::super::permissions::{self, Permission};
+use super::get::submission_files;
+
+pub enum ListSubmissionsOutcome {
+    Success(Vec<Submission>),
+    NotLoggedIn,
+    AssignmentNotFound,
+}
+
+impl Database {
+    /// Attempts to list the submissions for an assignment using the logged in user's ID and a given assignment ID.
+    ///
+    /// Owners and teachers of the room see every submission, students only see their own.
+    pub async fn list_submissions(
+        &self,
+        session_uuid: String,
+        assignment_id: i32,
+    ) -> Result<ListSubmissionsOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| session::user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(ListSubmissionsOutcome::NotLoggedIn);
+        };
+
+        let role = conn
+            .interact(move |conn| -> Result<_> {
+                let room_id: Option<i32> = conn
+                    .query_row(
+                        "SELECT room_id FROM assignments WHERE id = ?1",
+                        params![assignment_id],
+                        |row| row.get(0),
+                    )
+                    .optional()?;
+
+                match room_id {
+                    Some(room_id) if !permissions::is_hidden_from(conn, room_id, user_id)? => {
+                        Ok(permissions::room_role(conn, room_id, user_id)?)
+                    }
+                    _ => Ok(None),
+                }
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(role) = role else {
+            return Ok(ListSubmissionsOutcome::AssignmentNotFound);
+        };
+
+        let sees_all = role.can(Permission::ViewAllSubmissions);
+
+        let submissions = conn
+            .interact(move |conn| -> Result<Vec<Submission>> {
+                let mut stmt = conn.prepare(
+                    "
+                    SELECT id, assignment_id, user_id, submitted_at
+                    FROM submissions
+                    WHERE assignment_id = ?1 AND (?2 OR user_id = ?3)
+                    ORDER BY submitted_at, id
+                    ",
+                )?;
+
+                let submissions_iter = stmt.query_map(params![assignment_id, sees_all, user_id], |row| {
+                    Ok(Submission {
+                        id: row.get(0)?,
+                        assignment_id: row.get(1)?,
+                        user_id: row.get(2)?,
+                        submitted_at: row.get(3)?,
+                        files: Vec::new(),
+                    })
+                })?;
+
+                let mut submissions = Vec::new();
+                for submission in submissions_iter {
+                    let mut submission = submission?;
+                    submission.files = submission_files(conn, submission.id)?;
+                    submissions.push(submission);
+                }
+
+                Ok(submissions)
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        Ok(ListSubmissionsOutcome::Success(submissions))
+    }
+}
@@ -0,0 +1,14 @@
+mod can_submit;
+pub use can_submit::CanSubmitOutcome;
+
+mod create;
+pub use create::CreateSubmissionOutcome;
+
+mod file;
+pub use file::GetSubmissionFileOutcome;
+
+mod get;
+pub use get::GetSubmissionOutcome;
+
+mod list;
+pub use list::ListSubmissionsOutcome;
@@ -0,0 +1,108 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::{Connection, OptionalExtension, params};
+use totp_rs::{Algorithm, Secret, TOTP};
+
+use super::utils::{self, now};
+
+const ISSUER: &str = "Crate";
+const STEP: u64 = 30;
+const RECOVERY_CODE_COUNT: usize = 10;
+
+/// Generates a new base32 encoded TOTP secret.
+pub fn generate_secret() -> String {
+    match Secret::generate_secret().to_encoded() {
+        Secret::Encoded(secret) => secret,
+        Secret::Raw(_) => unreachable!("to_encoded always returns an encoded secret"),
+    }
+}
+
+/// Builds the TOTP generator for a base32 encoded secret, labelled with the user's email.
+pub fn totp(secret: &str, account_name: String) -> Result<TOTP> {
+    let secret = Secret::Encoded(secret.to_string())
+        .to_bytes()
+        .map_err(|e| anyhow!("invalid TOTP secret: {e:?}"))?;
+
+    // Unchecked, as emails may contain characters the checked constructor rejects
+    Ok(TOTP::new_unchecked(Algorithm::SHA1, 6, 1, STEP, secret, Some(ISSUER.to_string()), account_name))
+}
+
+/// Finds the time step a code belongs to, allowing one step of clock drift either way.
+pub fn matching_step(totp: &TOTP, code: &str) -> Option<i64> {
+    let current = u64::try_from(now()).unwrap_or(0) / STEP;
+
+    (current.saturating_sub(1)..=current + 1)
+        .find(|step| constant_time_eq(totp.generate(step * STEP).as_bytes(), code.as_bytes()))
+        .and_then(|step| i64::try_from(step).ok())
+}
+
+/// Generates a fresh set of recovery codes, e.g. `3f9a-0c1e-77b2-d4e8`.
+pub fn generate_recovery_codes() -> Vec<String> {
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let token = utils::generate_token();
+            format!("{}-{}-{}-{}", &token[0..4], &token[4..8], &token[8..12], &token[12..16])
+        })
+        .collect()
+}
+
+/// Recovery codes are compared without dashes, spaces or case.
+fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Hashes a recovery code for storage.
+pub fn hash_recovery_code(code: &str) -> String {
+    utils::hash_token(&normalize_recovery_code(code))
+}
+
+/// Checks a second factor for a user with TOTP enabled and uses it up.
+///
+/// The code is either a TOTP code newer than the last one accepted, or a recovery code
+/// that has not been used yet. Meant to be called from inside a transaction.
+pub fn verify_second_factor(conn: &Connection, user_id: i64, code: &str) -> Result<bool> {
+    let credentials: Option<(String, i64, String)> = conn
+        .query_row(
+            "SELECT t.secret, t.last_used_step, u.email
+            FROM totp_credentials t
+            JOIN users u ON u.id = t.user_id
+            WHERE t.user_id = ?1 AND t.enabled_at IS NOT NULL",
+            params![user_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()?;
+
+    let Some((secret, last_used_step, email)) = credentials else {
+        return Ok(false);
+    };
+
+    let code = code.trim();
+
+    if let Some(step) = matching_step(&totp(&secret, email)?, code) {
+        // A code may only be used once, even while it is still current
+        if step <= last_used_step {
+            return Ok(false);
+        }
+
+        conn.execute(
+            "UPDATE totp_credentials SET last_used_step = ?1 WHERE user_id = ?2",
+            params![step, user_id],
+        )?;
+
+        return Ok(true);
+    }
+
+    let used = conn.execute(
+        "UPDATE recovery_codes SET used_at = ?1
+        WHERE user_id = ?2 AND code_hash = ?3 AND used_at IS NULL",
+        params![now(), user_id, hash_recovery_code(code)],
+    )?;
+
+    Ok(used > 0)
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
@@ -0,0 +1,208 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::{OptionalExtension, params};
+
+use crate::types::{ApiToken, ApiTokenScope, CreatedApiToken, NewApiToken};
+use super::super::Database;
+use super::super::session::{self, API_TOKEN_PREFIX};
+use super::super::utils::{self, now};
+
+/// How long an API token works if no expiry is given, in seconds.
+const DEFAULT_API_TOKEN_TTL: i64 = 90 * 24 * 60 * 60;
+
+/// The longest an API token may work, in seconds.
+const MAX_API_TOKEN_TTL: i64 = 365 * 24 * 60 * 60;
+
+pub enum CreateApiTokenOutcome {
+    Success(CreatedApiToken),
+    NotLoggedIn,
+    InvalidSettings,
+}
+
+pub enum ListApiTokensOutcome {
+    Success(Vec<ApiToken>),
+    NotLoggedIn,
+}
+
+pub enum RevokeApiTokenOutcome {
+    Success,
+    NotLoggedIn,
+    TokenNotFound,
+}
+
+impl Database {
+    /// Creates an API token for the logged in user.
+    ///
+    /// Only a hash of the token is stored, so the returned token cannot be shown again.
+    /// Creating tokens takes a session, an API token cannot create another one.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(CreateApiTokenOutcome::Success(token))` with the new token.
+    /// - `Ok(CreateApiTokenOutcome::NotLoggedIn)` if the session does not exist or has expired.
+    /// - `Ok(CreateApiTokenOutcome::InvalidSettings)` if the name is empty or the expiry is out of range.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - a database connection cannot be acquired from the pool
+    /// - a database task fails to run or complete
+    /// - executing any SQL query fails
+    pub async fn create_api_token(&self, session_uuid: String, settings: NewApiToken) -> Result<CreateApiTokenOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| session::session_user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(CreateApiTokenOutcome::NotLoggedIn);
+        };
+
+        let name = settings.name.trim().to_string();
+        let ttl = settings.expires_in.unwrap_or(DEFAULT_API_TOKEN_TTL);
+
+        if name.is_empty() || ttl <= 0 || ttl > MAX_API_TOKEN_TTL {
+            return Ok(CreateApiTokenOutcome::InvalidSettings);
+        }
+
+        let token = format!("{API_TOKEN_PREFIX}{}", utils::generate_token());
+        let token_hash = utils::hash_token(&token);
+        let created_at = now();
+        let expires_at = created_at + ttl;
+
+        let id = conn
+            .interact(move |conn| {
+                conn.execute(
+                    "INSERT INTO api_tokens (user_id, name, token_hash, scope, created_at, expires_at)
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![user_id, name, token_hash, settings.scope, created_at, expires_at],
+                )?;
+                Ok::<_, deadpool_sqlite::rusqlite::Error>(conn.last_insert_rowid())
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        Ok(CreateApiTokenOutcome::Success(CreatedApiToken { id, token, expires_at }))
+    }
+
+    /// Lists the API tokens of the logged in user that have not expired, newest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - a database connection cannot be acquired from the pool
+    /// - a database task fails to run or complete
+    /// - executing any SQL query fails
+    pub async fn list_api_tokens(&self, session_uuid: String) -> Result<ListApiTokensOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| session::session_user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(ListApiTokensOutcome::NotLoggedIn);
+        };
+
+        let tokens = conn
+            .interact(move |conn| -> Result<Vec<ApiToken>> {
+                let mut stmt = conn.prepare(
+                    "
+                    SELECT id, name, scope, created_at, last_used_at, expires_at
+                    FROM api_tokens
+                    WHERE user_id = ?1 AND expires_at > ?2
+                    ORDER BY created_at DESC, id DESC
+                    ",
+                )?;
+
+                let tokens_iter = stmt.query_map(params![user_id, now()], |row| {
+                    Ok(ApiToken {
+                        id: row.get(0)?,
+                        name: row.get(1)?,
+                        scope: row.get(2)?,
+                        created_at: row.get(3)?,
+                        last_used_at: row.get(4)?,
+                        expires_at: row.get(5)?,
+                    })
+                })?;
+
+                let mut tokens = Vec::new();
+                for token in tokens_iter {
+                    tokens.push(token?);
+                }
+
+                Ok(tokens)
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        Ok(ListApiTokensOutcome::Success(tokens))
+    }
+
+    /// Revokes one of the logged in user's API tokens.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - a database connection cannot be acquired from the pool
+    /// - a database task fails to run or complete
+    /// - executing any SQL query fails
+    pub async fn revoke_api_token(&self, session_uuid: String, id: i64) -> Result<RevokeApiTokenOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| session::session_user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(RevokeApiTokenOutcome::NotLoggedIn);
+        };
+
+        let deleted = conn
+            .interact(move |conn| {
+                conn.execute(
+                    "DELETE FROM api_tokens WHERE id = ?1 AND user_id = ?2",
+                    params![id, user_id],
+                )
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        if deleted == 0 {
+            Ok(RevokeApiTokenOutcome::TokenNotFound)
+        } else {
+            Ok(RevokeApiTokenOutcome::Success)
+        }
+    }
+
+    /// Looks up what an API token may do, `None` if it does not exist or has expired.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - a database connection cannot be acquired from the pool
+    /// - a database task fails to run or complete
+    /// - executing the SQL query fails
+    pub async fn api_token_scope(&self, token: String) -> Result<Option<ApiTokenScope>> {
+        let conn = self.pool.get().await?;
+
+        let token_hash = utils::hash_token(&token);
+
+        let scope = conn
+            .interact(move |conn| {
+                conn.query_row(
+                    "SELECT scope FROM api_tokens WHERE token_hash = ?1 AND expires_at > ?2",
+                    params![token_hash, now()],
+                    |row| row.get(0),
+                )
+                .optional()
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        Ok(scope)
+    }
+}
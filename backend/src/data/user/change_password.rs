@@ -0,0 +1,97 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::params;
+
+use crate::types::PasswordChange;
+use super::super::Database;
+use super::super::session;
+use super::super::utils;
+
+pub enum ChangePasswordOutcome {
+    Success,
+    NotLoggedIn,
+    InvalidCredentials,
+}
+
+impl Database {
+    /// Changes the password of the logged in user after checking their current password.
+    ///
+    /// Every other session of the user is logged out and their API tokens and any pending
+    /// password reset tokens are revoked, since whoever knew the old password could have made them.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(ChangePasswordOutcome::Success)` if the password was changed.
+    /// - `Ok(ChangePasswordOutcome::NotLoggedIn)` if the session does not exist or has expired.
+    /// - `Ok(ChangePasswordOutcome::InvalidCredentials)` if the current password is wrong.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - a database connection cannot be acquired from the pool
+    /// - a database task fails to run or complete
+    /// - executing any SQL query fails
+    /// - password hashing fails
+    pub async fn change_password(
+        &self,
+        session_uuid: String,
+        change: PasswordChange,
+    ) -> Result<ChangePasswordOutcome> {
+        let conn = self.pool.get().await?;
+
+        let current_uuid = session_uuid.clone();
+        let user_id = conn
+            .interact(move |conn| session::session_user_id(conn, &current_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(ChangePasswordOutcome::NotLoggedIn);
+        };
+
+        let hash: String = conn
+            .interact(move |conn| {
+                conn.query_row(
+                    "SELECT password_hash FROM users WHERE id = ?1",
+                    params![user_id],
+                    |row| row.get(0),
+                )
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        if !utils::verify_password(&change.current_password, &hash) {
+            return Ok(ChangePasswordOutcome::InvalidCredentials);
+        }
+
+        let password_hash = utils::hash_password(&change.new_password)?;
+
+        conn.interact(move |conn| -> Result<()> {
+            let tx = conn.transaction()?;
+
+            tx.execute(
+                "UPDATE users SET password_hash = ?1 WHERE id = ?2",
+                params![password_hash, user_id],
+            )?;
+
+            tx.execute(
+                "DELETE FROM sessions WHERE user_id = ?1 AND uuid != ?2",
+                params![user_id, session_uuid],
+            )?;
+
+            tx.execute("DELETE FROM api_tokens WHERE user_id = ?1", params![user_id])?;
+
+            tx.execute(
+                "DELETE FROM password_reset_tokens WHERE user_id = ?1",
+                params![user_id],
+            )?;
+
+            tx.commit()?;
+
+            Ok(())
+        })
+        .await
+        .map_err(|e| anyhow!("{e}"))??;
+
+        Ok(ChangePasswordOutcome::Success)
+    }
+}
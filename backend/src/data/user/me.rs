@@ -0,0 +1,87 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::params;
+
+use crate::types::{CurrentUser, RoomMembership};
+use super::super::Database;
+use super::super::session;
+use super::super::permissions;
+
+pub enum GetCurrentUserOutcome {
+    Success(CurrentUser),
+    NotLoggedIn,
+}
+
+impl Database {
+    /// Gets the profile of the logged in user along with every room they are a member of.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(GetCurrentUserOutcome::Success(user))` with the user's profile and room memberships.
+    /// - `Ok(GetCurrentUserOutcome::NotLoggedIn)` if the session does not exist or has expired.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - a database connection cannot be acquired from the pool
+    /// - a database task fails to run or complete
+    /// - executing any SQL query fails
+    pub async fn get_current_user(&self, session_uuid: String) -> Result<GetCurrentUserOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| session::user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(GetCurrentUserOutcome::NotLoggedIn);
+        };
+
+        let user = conn
+            .interact(move |conn| -> Result<CurrentUser> {
+                let mut user = conn.query_row(
+                    "SELECT id, name, surname, email, email_verified_at IS NOT NULL FROM users WHERE id = ?1",
+                    params![user_id],
+                    |row| {
+                        Ok(CurrentUser {
+                            id: row.get(0)?,
+                            name: row.get(1)?,
+                            surname: row.get(2)?,
+                            email: row.get(3)?,
+                            email_verified: row.get(4)?,
+                            rooms: Vec::new(),
+                        })
+                    },
+                )?;
+
+                let mut stmt = conn.prepare(&format!(
+                    "
+                    SELECT r.id, r.name, rm.role
+                    FROM room_members rm
+                    JOIN rooms r ON r.id = rm.room_id
+                    WHERE rm.user_id = ?1 AND r.deleted_at IS NULL AND {}
+                    ORDER BY r.name, r.id
+                    ",
+                    permissions::NOT_HIDDEN,
+                ))?;
+
+                let rooms_iter = stmt.query_map(params![user_id], |row| {
+                    Ok(RoomMembership {
+                        room_id: row.get(0)?,
+                        name: row.get(1)?,
+                        role: row.get(2)?,
+                    })
+                })?;
+
+                for room in rooms_iter {
+                    user.rooms.push(room?);
+                }
+
+                Ok(user)
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        Ok(GetCurrentUserOutcome::Success(user))
+    }
+}
@@ -1,2 +1,28 @@
+mod api_tokens;
+pub use api_tokens::CreateApiTokenOutcome;
+pub use api_tokens::ListApiTokensOutcome;
+pub use api_tokens::RevokeApiTokenOutcome;
+
+mod change_password;
+pub use change_password::ChangePasswordOutcome;
+
+mod me;
+pub use me::GetCurrentUserOutcome;
+
+mod password_reset;
+pub use password_reset::ConfirmPasswordResetOutcome;
+pub use password_reset::RequestPasswordResetOutcome;
+
 mod register;
 pub use register::RegisterOutcome;
+
+mod totp;
+pub use totp::ConfirmTotpEnrollmentOutcome;
+pub use totp::DisableTotpOutcome;
+pub use totp::StartTotpEnrollmentOutcome;
+
+mod update_profile;
+pub use update_profile::UpdateProfileOutcome;
+
+mod verify_email;
+pub use verify_email::VerifyEmailOutcome;
@@ -0,0 +1,157 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::{OptionalExtension, TransactionBehavior, params};
+
+use super::super::Database;
+use super::super::utils::{self, now};
+
+/// How long a password reset token can be used after it was requested, in seconds.
+const PASSWORD_RESET_TTL: i64 = 60 * 60;
+
+pub enum RequestPasswordResetOutcome {
+    /// The plain token, to be sent to the user and never stored
+    Success(String),
+    UserDoesNotExist,
+}
+
+pub enum ConfirmPasswordResetOutcome {
+    Success,
+    InvalidToken,
+}
+
+impl Database {
+    /// Creates a password reset token for the user with the given email.
+    ///
+    /// Only a hash of the token is stored, and any token the user requested before is discarded.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(RequestPasswordResetOutcome::Success(token))` with the token to send to the user.
+    /// - `Ok(RequestPasswordResetOutcome::UserDoesNotExist)` if no user exists with the given email.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - a database connection cannot be acquired from the pool
+    /// - a database task fails to run or complete
+    /// - executing any SQL query fails
+    pub async fn request_password_reset(&self, email: String) -> Result<RequestPasswordResetOutcome> {
+        let conn = self.pool.get().await?;
+
+        let email = utils::normalize_email(&email);
+        let token = utils::generate_token();
+        let token_hash = utils::hash_token(&token);
+
+        let created = conn
+            .interact(move |conn| -> Result<bool> {
+                let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+
+                let user_id: Option<i64> = tx
+                    .query_row(
+                        "SELECT id FROM users WHERE email = ?1",
+                        params![email],
+                        |row| row.get(0),
+                    )
+                    .optional()?;
+
+                let Some(user_id) = user_id else {
+                    return Ok(false);
+                };
+
+                tx.execute(
+                    "DELETE FROM password_reset_tokens WHERE user_id = ?1",
+                    params![user_id],
+                )?;
+
+                let now = now();
+                tx.execute(
+                    "INSERT INTO password_reset_tokens (token_hash, user_id, created_at, expires_at)
+                    VALUES (?1, ?2, ?3, ?4)",
+                    params![token_hash, user_id, now, now + PASSWORD_RESET_TTL],
+                )?;
+
+                tx.commit()?;
+
+                Ok(true)
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        if created {
+            Ok(RequestPasswordResetOutcome::Success(token))
+        } else {
+            Ok(RequestPasswordResetOutcome::UserDoesNotExist)
+        }
+    }
+
+    /// Sets a new password using a token from [`Database::request_password_reset`].
+    ///
+    /// The token is used up, every session of the user is logged out and their API tokens are revoked.
+    /// A lockout from wrong passwords is lifted, so the new password works right away.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(ConfirmPasswordResetOutcome::Success)` if the password was changed.
+    /// - `Ok(ConfirmPasswordResetOutcome::InvalidToken)` if the token does not exist, has expired or was already used.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - a database connection cannot be acquired from the pool
+    /// - a database task fails to run or complete
+    /// - executing any SQL query fails
+    /// - password hashing fails
+    pub async fn confirm_password_reset(
+        &self,
+        token: String,
+        password: String,
+    ) -> Result<ConfirmPasswordResetOutcome> {
+        let conn = self.pool.get().await?;
+
+        let token_hash = utils::hash_token(&token);
+        let password_hash = utils::hash_password(&password)?;
+
+        conn.interact(move |conn| -> Result<ConfirmPasswordResetOutcome> {
+            let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+            let now = now();
+
+            let user_id: Option<i64> = tx
+                .query_row(
+                    "SELECT user_id FROM password_reset_tokens
+                    WHERE token_hash = ?1 AND used_at IS NULL AND expires_at > ?2",
+                    params![token_hash, now],
+                    |row| row.get(0),
+                )
+                .optional()?;
+
+            let Some(user_id) = user_id else {
+                return Ok(ConfirmPasswordResetOutcome::InvalidToken);
+            };
+
+            tx.execute(
+                "UPDATE password_reset_tokens SET used_at = ?1 WHERE token_hash = ?2",
+                params![now, token_hash],
+            )?;
+
+            tx.execute(
+                "UPDATE users SET password_hash = ?1 WHERE id = ?2",
+                params![password_hash, user_id],
+            )?;
+
+            // Whoever knew the old password should not stay logged in
+            tx.execute("DELETE FROM sessions WHERE user_id = ?1", params![user_id])?;
+            tx.execute("DELETE FROM api_tokens WHERE user_id = ?1", params![user_id])?;
+
+            let email: String = tx.query_row("SELECT email FROM users WHERE id = ?1", params![user_id], |row| row.get(0))?;
+            tx.execute(
+                "DELETE FROM login_failures WHERE email = ?1",
+                params![utils::normalize_email(&email)],
+            )?;
+
+            tx.commit()?;
+
+            Ok(ConfirmPasswordResetOutcome::Success)
+        })
+        .await
+        .map_err(|e| anyhow!("{e}"))?
+    }
+}
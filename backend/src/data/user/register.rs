@@ -1,12 +1,13 @@
 use anyhow::{anyhow, Result};
-use deadpool_sqlite::rusqlite::{OptionalExtension, params};
+use deadpool_sqlite::rusqlite::{OptionalExtension, TransactionBehavior, params};
 
 use super::super::Database;
 use super::super::utils;
 use crate::types::NewUser;
 
 pub enum RegisterOutcome {
-    Success,
+    /// ID of the new user, whose email is not verified yet
+    Success(i64),
     UserAlreadyExists,
 }
 
@@ -14,7 +15,8 @@ impl Database {
     /// Registers a new user in the `users` table.
     ///
     /// If a user with the same email already exists, no insertion is performed and
-    /// [`RegisterOutcome::UserAlreadyExists`] is returned.
+    /// [`RegisterOutcome::UserAlreadyExists`] is returned. The password is hashed either way,
+    /// so both cases take about as long.
     ///
     /// # Errors
     ///
@@ -26,34 +28,38 @@ impl Database {
     pub async fn register_user(&self, user: NewUser) -> Result<RegisterOutcome> {
         let conn = self.pool.get().await?;
 
-        let email = user.email.clone();
-        let existing_user: Option<i64> = conn
+        let password_hash = utils::hash_password(&user.password)?;
+        let email = utils::normalize_email(&user.email);
+
+        let outcome = conn
             .interact(move |conn| {
-                conn.query_row(
-                    "SELECT id FROM users WHERE email = ?1",
-                    params![email],
-                    |row| row.get::<_, i64>(0),
-                )
-                .optional()
-            })
-            .await
-            .map_err(|e| anyhow!("{e}"))??;
+                // Immediate, so two registrations with the same email cannot both pass the check
+                let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
 
-        if existing_user.is_some() {
-            return Ok(RegisterOutcome::UserAlreadyExists);
-        }
+                let existing_user: Option<i64> = tx
+                    .query_row(
+                        "SELECT id FROM users WHERE email = ?1",
+                        params![email],
+                        |row| row.get::<_, i64>(0),
+                    )
+                    .optional()?;
 
-        let password_hash = utils::hash_password(&user.password)?;
+                if existing_user.is_some() {
+                    return Ok(RegisterOutcome::UserAlreadyExists);
+                }
+
+                tx.execute(
+                    "INSERT INTO users (email, name, surname, password_hash) VALUES (?1, ?2, ?3, ?4)",
+                    params![email, user.name, user.surname, password_hash],
+                )?;
+                let user_id = tx.last_insert_rowid();
+                tx.commit()?;
 
-        conn.interact(move |conn| {
-            conn.execute(
-                "INSERT INTO users (email, name, surname, password_hash) VALUES (?1, ?2, ?3, ?4)",
-                params![user.email, user.name, user.surname, password_hash],
-            )
-        })
-        .await
-        .map_err(|e| anyhow!("{e}"))??;
+                Ok::<_, deadpool_sqlite::rusqlite::Error>(RegisterOutcome::Success(user_id))
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
 
-        Ok(RegisterOutcome::Success)
+        Ok(outcome)
     }
 }
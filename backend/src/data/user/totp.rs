@@ -0,0 +1,224 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::{OptionalExtension, TransactionBehavior, params};
+
+use crate::types::TotpEnrollment;
+use super::super::Database;
+use super::super::session;
+use super::super::totp;
+use super::super::utils::{self, now};
+
+pub enum StartTotpEnrollmentOutcome {
+    Success(TotpEnrollment),
+    NotLoggedIn,
+    AlreadyEnabled,
+}
+
+pub enum ConfirmTotpEnrollmentOutcome {
+    /// Carries the recovery codes, which are only ever shown this once
+    Success(Vec<String>),
+    NotLoggedIn,
+    NotStarted,
+    AlreadyEnabled,
+    InvalidCode,
+}
+
+pub enum DisableTotpOutcome {
+    Success,
+    NotLoggedIn,
+    NotEnabled,
+    InvalidCredentials,
+}
+
+impl Database {
+    /// Starts TOTP enrollment for the logged in user by generating a new secret.
+    ///
+    /// TOTP is not required at login until the enrollment is confirmed with
+    /// [`Database::confirm_totp_enrollment`], starting over replaces an unconfirmed secret.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - a database connection cannot be acquired from the pool
+    /// - a database task fails to run or complete
+    /// - executing any SQL query fails
+    pub async fn start_totp_enrollment(&self, session_uuid: String) -> Result<StartTotpEnrollmentOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| session::session_user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(StartTotpEnrollmentOutcome::NotLoggedIn);
+        };
+
+        conn.interact(move |conn| -> Result<StartTotpEnrollmentOutcome> {
+            let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+
+            let enabled: bool = tx.query_row(
+                "SELECT EXISTS(SELECT 1 FROM totp_credentials WHERE user_id = ?1 AND enabled_at IS NOT NULL)",
+                params![user_id],
+                |row| row.get(0),
+            )?;
+
+            if enabled {
+                return Ok(StartTotpEnrollmentOutcome::AlreadyEnabled);
+            }
+
+            let email: String = tx.query_row(
+                "SELECT email FROM users WHERE id = ?1",
+                params![user_id],
+                |row| row.get(0),
+            )?;
+
+            let secret = totp::generate_secret();
+            let otpauth_uri = totp::totp(&secret, email)?.get_url();
+
+            tx.execute(
+                "INSERT INTO totp_credentials (user_id, secret) VALUES (?1, ?2)
+                ON CONFLICT(user_id) DO UPDATE SET secret = excluded.secret, last_used_step = 0",
+                params![user_id, secret],
+            )?;
+
+            tx.commit()?;
+
+            Ok(StartTotpEnrollmentOutcome::Success(TotpEnrollment { secret, otpauth_uri }))
+        })
+        .await
+        .map_err(|e| anyhow!("{e}"))?
+    }
+
+    /// Confirms TOTP enrollment with a code from the user's authenticator app, which turns on
+    /// the second factor at login and replaces any previous recovery codes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - a database connection cannot be acquired from the pool
+    /// - a database task fails to run or complete
+    /// - executing any SQL query fails
+    pub async fn confirm_totp_enrollment(
+        &self,
+        session_uuid: String,
+        code: String,
+    ) -> Result<ConfirmTotpEnrollmentOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| session::session_user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(ConfirmTotpEnrollmentOutcome::NotLoggedIn);
+        };
+
+        conn.interact(move |conn| -> Result<ConfirmTotpEnrollmentOutcome> {
+            let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+
+            let credentials: Option<(String, bool, String)> = tx
+                .query_row(
+                    "SELECT t.secret, t.enabled_at IS NOT NULL, u.email
+                    FROM totp_credentials t
+                    JOIN users u ON u.id = t.user_id
+                    WHERE t.user_id = ?1",
+                    params![user_id],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+                )
+                .optional()?;
+
+            let Some((secret, enabled, email)) = credentials else {
+                return Ok(ConfirmTotpEnrollmentOutcome::NotStarted);
+            };
+
+            if enabled {
+                return Ok(ConfirmTotpEnrollmentOutcome::AlreadyEnabled);
+            }
+
+            let Some(step) = totp::matching_step(&totp::totp(&secret, email)?, code.trim()) else {
+                return Ok(ConfirmTotpEnrollmentOutcome::InvalidCode);
+            };
+
+            tx.execute(
+                "UPDATE totp_credentials SET enabled_at = ?1, last_used_step = ?2 WHERE user_id = ?3",
+                params![now(), step, user_id],
+            )?;
+
+            tx.execute("DELETE FROM recovery_codes WHERE user_id = ?1", params![user_id])?;
+
+            let recovery_codes = totp::generate_recovery_codes();
+            for code in &recovery_codes {
+                tx.execute(
+                    "INSERT INTO recovery_codes (user_id, code_hash) VALUES (?1, ?2)",
+                    params![user_id, totp::hash_recovery_code(code)],
+                )?;
+            }
+
+            tx.commit()?;
+
+            Ok(ConfirmTotpEnrollmentOutcome::Success(recovery_codes))
+        })
+        .await
+        .map_err(|e| anyhow!("{e}"))?
+    }
+
+    /// Turns off TOTP for the logged in user after checking their password.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - a database connection cannot be acquired from the pool
+    /// - a database task fails to run or complete
+    /// - executing any SQL query fails
+    pub async fn disable_totp(&self, session_uuid: String, password: String) -> Result<DisableTotpOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| session::session_user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(DisableTotpOutcome::NotLoggedIn);
+        };
+
+        let (hash, enabled): (String, bool) = conn
+            .interact(move |conn| {
+                conn.query_row(
+                    "SELECT u.password_hash, t.enabled_at IS NOT NULL
+                    FROM users u
+                    LEFT JOIN totp_credentials t ON t.user_id = u.id
+                    WHERE u.id = ?1",
+                    params![user_id],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        if !enabled {
+            return Ok(DisableTotpOutcome::NotEnabled);
+        }
+
+        if !utils::verify_password(&password, &hash) {
+            return Ok(DisableTotpOutcome::InvalidCredentials);
+        }
+
+        conn.interact(move |conn| -> Result<()> {
+            let tx = conn.transaction()?;
+
+            tx.execute("DELETE FROM totp_credentials WHERE user_id = ?1", params![user_id])?;
+            tx.execute("DELETE FROM recovery_codes WHERE user_id = ?1", params![user_id])?;
+            tx.execute("DELETE FROM login_challenges WHERE user_id = ?1", params![user_id])?;
+
+            tx.commit()?;
+
+            Ok(())
+        })
+        .await
+        .map_err(|e| anyhow!("{e}"))??;
+
+        Ok(DisableTotpOutcome::Success)
+    }
+}
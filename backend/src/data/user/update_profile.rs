@@ -0,0 +1,124 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::{TransactionBehavior, params};
+
+use crate::types::ProfileUpdate;
+use super::super::Database;
+use super::super::session;
+use super::super::utils;
+
+pub enum UpdateProfileOutcome {
+    Success,
+    /// The email was changed and has to be verified again, carries the user's ID
+    EmailChanged(i64),
+    NotLoggedIn,
+    EmailAlreadyInUse,
+    /// The email would change and the current password is missing or wrong
+    InvalidCredentials,
+}
+
+impl Database {
+    /// Updates the name, surname and email of the logged in user, leaving out fields that are `None`.
+    ///
+    /// A new email is marked as unverified. Changing the email takes the current password, and
+    /// any pending password reset tokens are revoked so they cannot be used to get back in.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(UpdateProfileOutcome::Success)` if the profile was updated and the email stayed the same.
+    /// - `Ok(UpdateProfileOutcome::EmailChanged(user_id))` if the profile was updated with a new email.
+    /// - `Ok(UpdateProfileOutcome::NotLoggedIn)` if the session does not exist or has expired.
+    /// - `Ok(UpdateProfileOutcome::EmailAlreadyInUse)` if another user already has the new email.
+    /// - `Ok(UpdateProfileOutcome::InvalidCredentials)` if the email would change and the current
+    ///   password is missing or wrong.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - a database connection cannot be acquired from the pool
+    /// - a database task fails to run or complete
+    /// - executing any SQL query fails
+    pub async fn update_profile(
+        &self,
+        session_uuid: String,
+        update: ProfileUpdate,
+    ) -> Result<UpdateProfileOutcome> {
+        let conn = self.pool.get().await?;
+
+        let user_id = conn
+            .interact(move |conn| session::session_user_id(conn, &session_uuid))
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        let Some(user_id) = user_id else {
+            return Ok(UpdateProfileOutcome::NotLoggedIn);
+        };
+
+        let email = update.email.as_deref().map(utils::normalize_email);
+
+        conn.interact(move |conn| -> Result<UpdateProfileOutcome> {
+            let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+
+            let mut email_changed = false;
+            if let Some(email) = &email {
+                let (current_email, hash): (String, String) = tx.query_row(
+                    "SELECT email, password_hash FROM users WHERE id = ?1",
+                    params![user_id],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )?;
+
+                if *email != current_email {
+                    let Some(current_password) = &update.current_password else {
+                        return Ok(UpdateProfileOutcome::InvalidCredentials);
+                    };
+
+                    if !utils::verify_password(current_password, &hash) {
+                        return Ok(UpdateProfileOutcome::InvalidCredentials);
+                    }
+
+                    let taken: bool = tx.query_row(
+                        "SELECT EXISTS(SELECT 1 FROM users WHERE email = ?1 AND id != ?2)",
+                        params![email, user_id],
+                        |row| row.get(0),
+                    )?;
+
+                    if taken {
+                        return Ok(UpdateProfileOutcome::EmailAlreadyInUse);
+                    }
+
+                    email_changed = true;
+                }
+            }
+
+            tx.execute(
+                "UPDATE users SET
+                    name = COALESCE(?1, name),
+                    surname = COALESCE(?2, surname),
+                    email = COALESCE(?3, email)
+                WHERE id = ?4",
+                params![update.name, update.surname, email, user_id],
+            )?;
+
+            if email_changed {
+                tx.execute(
+                    "UPDATE users SET email_verified_at = NULL WHERE id = ?1",
+                    params![user_id],
+                )?;
+
+                tx.execute(
+                    "DELETE FROM password_reset_tokens WHERE user_id = ?1",
+                    params![user_id],
+                )?;
+            }
+
+            tx.commit()?;
+
+            if email_changed {
+                Ok(UpdateProfileOutcome::EmailChanged(user_id))
+            } else {
+                Ok(UpdateProfileOutcome::Success)
+            }
+        })
+        .await
+        .map_err(|e| anyhow!("{e}"))?
+    }
+}
@@ -0,0 +1,95 @@
+use anyhow::{anyhow, Result};
+use deadpool_sqlite::rusqlite::{OptionalExtension, params};
+
+use super::super::Database;
+use super::super::utils::{self, now};
+
+pub enum VerifyEmailOutcome {
+    Success,
+    AlreadyVerified,
+    /// The user no longer exists or has changed their email since the link was sent
+    EmailChanged,
+}
+
+impl Database {
+    /// Marks the email of a user as verified, if it is still the email the link was sent to.
+    ///
+    /// The caller is expected to have checked the signature of the verification link.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - a database connection cannot be acquired from the pool
+    /// - a database task fails to run or complete
+    /// - executing the SQL query fails
+    pub async fn verify_email(&self, user_id: i64, email: String) -> Result<VerifyEmailOutcome> {
+        let conn = self.pool.get().await?;
+
+        let email = utils::normalize_email(&email);
+        let email_clone = email.clone();
+        let verified: Option<bool> = conn
+            .interact(move |conn| {
+                conn.query_row(
+                    "SELECT email_verified_at IS NOT NULL FROM users WHERE id = ?1 AND email = ?2",
+                    params![user_id, email_clone],
+                    |row| row.get(0),
+                )
+                .optional()
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        match verified {
+            None => return Ok(VerifyEmailOutcome::EmailChanged),
+            Some(true) => return Ok(VerifyEmailOutcome::AlreadyVerified),
+            Some(false) => {}
+        }
+
+        // The email is checked again, it may have changed since it was looked up
+        let updated = conn
+            .interact(move |conn| {
+                conn.execute(
+                    "UPDATE users SET email_verified_at = COALESCE(email_verified_at, ?1)
+                    WHERE id = ?2 AND email = ?3",
+                    params![now(), user_id, email],
+                )
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        if updated == 0 {
+            return Ok(VerifyEmailOutcome::EmailChanged);
+        }
+
+        Ok(VerifyEmailOutcome::Success)
+    }
+
+    /// Looks up the ID of the user with the given email, `None` if there is none or
+    /// their email is already verified.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - a database connection cannot be acquired from the pool
+    /// - a database task fails to run or complete
+    /// - executing the SQL query fails
+    pub async fn unverified_user_id(&self, email: String) -> Result<Option<i64>> {
+        let conn = self.pool.get().await?;
+
+        let email = utils::normalize_email(&email);
+
+        let user_id = conn
+            .interact(move |conn| {
+                conn.query_row(
+                    "SELECT id FROM users WHERE email = ?1 AND email_verified_at IS NULL",
+                    params![email],
+                    |row| row.get(0),
+                )
+                .optional()
+            })
+            .await
+            .map_err(|e| anyhow!("{e}"))??;
+
+        Ok(user_id)
+    }
+}
@@ -1,5 +1,8 @@
-use bcrypt::{hash, DEFAULT_COST};
+use bcrypt::{hash, verify, DEFAULT_COST};
 use rand::Rng;
+use sha2::{Digest, Sha256};
+use std::sync::LazyLock;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 pub fn generate_invitation_code() -> String {
     const CHARSET: &[u8] =
@@ -15,6 +18,51 @@ pub fn generate_invitation_code() -> String {
         .collect()
 }
 
+/// Generates a random secret token, hex encoded, to be handed out to a user once.
+pub fn generate_token() -> String {
+    let bytes: [u8; 32] = rand::rng().random();
+    hex::encode(bytes)
+}
+
+/// Hashes a token for storage, so a leaked database does not leak usable tokens.
+///
+/// Tokens are long and random, so a fast unsalted hash is enough.
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
 pub fn hash_password(password: &str) -> Result<String, bcrypt::BcryptError> {
     hash(password, DEFAULT_COST)
 }
+
+/// Checks a password against a stored hash.
+///
+/// A hash that cannot be parsed counts as a mismatch, so an account without a usable
+/// password is turned away like any wrong password instead of failing the request.
+pub fn verify_password(password: &str, hash: &str) -> bool {
+    verify(password, hash).unwrap_or(false)
+}
+
+/// A password hash no user has, computed once with the same cost as real hashes.
+///
+/// Verifying against it when an email is unknown makes the response take as long as
+/// for a wrong password, so timing does not tell which emails are registered.
+pub fn dummy_password_hash() -> &'static str {
+    static HASH: LazyLock<String> =
+        LazyLock::new(|| hash_password("not a real password").expect("bcrypt can hash a fixed password"));
+
+    &HASH
+}
+
+/// Brings an email to the form it is stored, looked up and counted wrong passwords under,
+/// so changing its case or adding spaces neither makes a new account nor starts a fresh count.
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+/// Returns the current time as a Unix timestamp in seconds.
+pub fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| i64::try_from(d.as_secs()).unwrap_or(i64::MAX))
+}
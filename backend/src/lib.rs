@@ -1,4 +1,13 @@
 pub mod cors;
+pub mod credential;
 pub mod data;
+pub mod mailer;
+pub mod oidc;
+pub mod rate_limit;
+pub mod registration;
 pub mod routes;
+pub mod state;
+pub mod trash;
 pub mod types;
+pub mod uploads;
+pub mod verification;
@@ -0,0 +1,128 @@
+use anyhow::{Context, Result};
+use lettre::{
+    message::{header::ContentType, Mailbox},
+    transport::smtp::authentication::Credentials,
+    Message, SmtpTransport, Transport,
+};
+use std::env;
+
+const DEFAULT_FROM: &str = "Crate <noreply@localhost>";
+const DEFAULT_APP_URL: &str = "http://localhost:5173";
+
+/// An email ready to be handed to a [`MailTransport`].
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Something that can deliver emails.
+///
+/// Sending blocks, so call it from `spawn_blocking` inside async code.
+pub trait MailTransport: Send + Sync {
+    fn send(&self, email: &Email) -> Result<()>;
+}
+
+/// Delivers emails to an SMTP server, either a real relay or a local stand-in like mailcatcher.
+pub struct SmtpMailTransport {
+    transport: SmtpTransport,
+    from: Mailbox,
+}
+
+impl MailTransport for SmtpMailTransport {
+    fn send(&self, email: &Email) -> Result<()> {
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(email.to.parse().context("invalid recipient address")?)
+            .subject(&email.subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(email.body.clone())?;
+
+        self.transport.send(&message)?;
+
+        Ok(())
+    }
+}
+
+/// Prints emails to stdout instead of sending them, used when no SMTP server is configured.
+pub struct LogMailTransport;
+
+impl MailTransport for LogMailTransport {
+    fn send(&self, email: &Email) -> Result<()> {
+        println!("Mail to {}: {}\n{}", email.to, email.subject, email.body);
+        Ok(())
+    }
+}
+
+pub struct Mailer {
+    transport: Box<dyn MailTransport>,
+    /// Base URL of the frontend, links in emails point there
+    pub app_url: String,
+}
+
+impl Mailer {
+    pub fn new(transport: Box<dyn MailTransport>, app_url: String) -> Self {
+        Self { transport, app_url }
+    }
+
+    /// Reads the mail configuration from the environment.
+    ///
+    /// - `SMTP_HOST`: SMTP server to send through, emails are only logged if unset
+    /// - `SMTP_PORT`: port of the SMTP server (default depends on `SMTP_TLS`)
+    /// - `SMTP_TLS`: one of `starttls` (default), `tls` or `none`
+    /// - `SMTP_USERNAME`, `SMTP_PASSWORD`: credentials, if the server requires them
+    /// - `MAIL_FROM`: sender of every email (default `Crate <noreply@localhost>`)
+    /// - `APP_URL`: base URL of the frontend (default `http://localhost:5173`)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `MAIL_FROM` is not a valid mailbox, `SMTP_TLS` has an unknown value
+    /// or the SMTP transport cannot be configured.
+    pub fn from_env() -> Result<Self> {
+        let app_url = env::var("APP_URL")
+            .unwrap_or_else(|_| DEFAULT_APP_URL.to_string())
+            .trim_end_matches('/')
+            .to_string();
+
+        let Ok(host) = env::var("SMTP_HOST") else {
+            println!("WARN: SMTP_HOST is not set, emails will only be logged");
+            return Ok(Self::new(Box::new(LogMailTransport), app_url));
+        };
+
+        let from = env::var("MAIL_FROM")
+            .unwrap_or_else(|_| DEFAULT_FROM.to_string())
+            .parse()
+            .context("MAIL_FROM is not a valid mailbox")?;
+
+        let mut builder = match env::var("SMTP_TLS").as_deref().unwrap_or("starttls") {
+            "starttls" => SmtpTransport::starttls_relay(&host)?,
+            "tls" => SmtpTransport::relay(&host)?,
+            "none" => SmtpTransport::builder_dangerous(&host),
+            other => anyhow::bail!("unknown SMTP_TLS value {other:?}"),
+        };
+
+        if let Some(port) = env::var("SMTP_PORT").ok().and_then(|v| v.parse().ok()) {
+            builder = builder.port(port);
+        }
+
+        if let (Ok(username), Ok(password)) = (env::var("SMTP_USERNAME"), env::var("SMTP_PASSWORD")) {
+            builder = builder.credentials(Credentials::new(username, password));
+        }
+
+        let transport = SmtpMailTransport {
+            transport: builder.build(),
+            from,
+        };
+
+        Ok(Self::new(Box::new(transport), app_url))
+    }
+
+    /// Sends an email, blocking until the transport is done with it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transport fails to deliver the email.
+    pub fn send(&self, email: &Email) -> Result<()> {
+        self.transport.send(email)
+    }
+}
@@ -1,18 +1,31 @@
 use anyhow::Result;
 use axum::{
-    routing::{delete, get, post},
+    extract::DefaultBodyLimit,
+    middleware,
+    routing::{delete, get, patch, post, put},
     Router,
 };
 use tower_cookies::CookieManagerLayer;
-use std::env;
+use std::{env, net::SocketAddr, sync::Arc, time::Duration};
 
-use backend::{data::Database, routes};
-use backend::cors;
+use backend::{data::{latest_schema_version, Database}, routes};
+use backend::{cors, mailer::Mailer, oidc::Oidc, state::AppState, uploads::UploadConfig, verification::EmailVerification};
+use backend::rate_limit::{self, RateLimitState, RateLimiter};
+use backend::registration::RegistrationConfig;
+use backend::trash::TrashConfig;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let dev = env::args().skip(1).any(|arg| arg == "--dev");
 
+    let database = Database::new().await?;
+
+    // Lets deployments migrate the database before swapping in the new server
+    if env::args().skip(1).any(|arg| arg == "--migrate-only") {
+        println!("Database schema is at version {}", latest_schema_version());
+        return Ok(());
+    }
+
     let cors_layer = if dev {
         println!("WARN: running in development mode");
         cors::dev()
@@ -20,26 +33,176 @@ async fn main() -> Result<()> {
         cors::prod()
     };
 
-    let database = Database::new().await?;
+    // Hash up front, otherwise the first login with an unknown email would be noticeably slower
+    tokio::task::spawn_blocking(backend::data::utils::dummy_password_hash).await?;
+
+    let uploads = UploadConfig::from_env();
+    let trash = TrashConfig::from_env();
+
+    // Expired sessions are rejected on lookup anyway, purging just keeps the table small.
+    // Rooms are purged once they have been in the trash for longer than the retention period.
+    let purge_db = database.clone();
+    let purge_upload_dir = uploads.dir.clone();
+    let retention = trash.retention;
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(60 * 60));
+        loop {
+            interval.tick().await;
+            if let Err(e) = purge_db.purge_expired_sessions().await {
+                eprintln!("Purge expired sessions error: {e}");
+            }
+            if let Err(e) = purge_db.purge_deleted_rooms(retention, &purge_upload_dir).await {
+                eprintln!("Purge deleted rooms error: {e}");
+            }
+        }
+    });
+
+    let upload_body_limit = DefaultBodyLimit::max(uploads.max_body_size());
+    let mailer = Mailer::from_env()?;
+    let verification = EmailVerification::from_env();
+    let registration = RegistrationConfig::from_env();
+    let oidc = Oidc::from_env()?;
+
+    // Per client IP and email, failed logins additionally lock the account itself
+    let login_limit = middleware::from_fn_with_state(
+        Arc::new(RateLimiter::new(10, Duration::from_secs(60))),
+        rate_limit::limit_login,
+    );
+    // Generous enough for a whole class signing up from behind one school network
+    let register_limit = middleware::from_fn_with_state(
+        RateLimitState {
+            limiter: Arc::new(RateLimiter::new(50, Duration::from_secs(60 * 60))),
+            db: database.clone(),
+        },
+        rate_limit::limit,
+    );
+    // Shared by the routes that mail existing accounts, per IP and per recipient so none of
+    // them can be used to flood an inbox
+    let mail_limit = middleware::from_fn_with_state(
+        RateLimitState {
+            limiter: Arc::new(RateLimiter::new(20, Duration::from_secs(60 * 60))),
+            db: database.clone(),
+        },
+        rate_limit::limit,
+    );
+    let recipient_limit = middleware::from_fn_with_state(
+        Arc::new(RateLimiter::new(3, Duration::from_secs(60 * 60))),
+        rate_limit::limit_recipient,
+    );
+    // Low enough that guessing invitation codes is hopeless
+    let join_limit = middleware::from_fn_with_state(
+        RateLimitState {
+            limiter: Arc::new(RateLimiter::new(10, Duration::from_secs(60))),
+            db: database.clone(),
+        },
+        rate_limit::limit,
+    );
+
+    let state = AppState {
+        db: database,
+        uploads: Arc::new(uploads),
+        mailer: Arc::new(mailer),
+        verification: Arc::new(verification),
+        registration: Arc::new(registration),
+        trash: Arc::new(trash),
+        oidc: oidc.map(Arc::new),
+    };
 
     let app = Router::new()
+        .route("/assignments/{id}", get(routes::assignments::get))
+        .route(
+            "/assignments/{id}/submissions",
+            get(routes::submissions::list)
+                .post(routes::submissions::create)
+                .layer(upload_body_limit),
+        )
         .route("/auth/is_logged_in", post(routes::auth::is_logged_in))
-        .route("/auth/login", post(routes::auth::login))
+        .route("/auth/login", post(routes::auth::login).route_layer(login_limit.clone()))
+        .route("/auth/login/totp", post(routes::auth::login_totp).route_layer(login_limit))
         .route("/auth/logout", post(routes::auth::logout))
-        .route("/auth/register", post(routes::auth::register))
+        .route("/auth/logout-others", post(routes::auth::logout_others))
+        .route("/auth/oidc/callback", get(routes::auth::oidc_callback))
+        .route("/auth/oidc/login", get(routes::auth::oidc_login))
+        .route("/auth/password-reset/confirm", post(routes::auth::confirm_password_reset))
+        .route(
+            "/auth/password-reset/request",
+            post(routes::auth::request_password_reset)
+                .route_layer(recipient_limit.clone())
+                .route_layer(mail_limit.clone()),
+        )
+        .route("/auth/register", post(routes::auth::register).route_layer(register_limit))
+        .route("/auth/sessions", get(routes::auth::sessions))
+        .route("/auth/sessions/{id}", delete(routes::auth::revoke_session))
+        .route(
+            "/auth/verify-email/resend",
+            post(routes::auth::resend_verification_email)
+                .route_layer(recipient_limit)
+                .route_layer(mail_limit),
+        )
+        .route("/auth/verify-email/{token}", get(routes::auth::verify_email))
+        .route("/grades", get(routes::grades::mine))
         .route("/health", get(routes::health::health))
         .route("/rooms/create", post(routes::rooms::create))
         .route("/rooms/get", get(routes::rooms::get))
-        .route("/rooms/join/{code}", post(routes::rooms::join))
+        .route("/rooms/join/{code}", post(routes::rooms::join).route_layer(join_limit))
+        .route("/rooms/join-requests", get(routes::rooms::my_join_requests))
+        .route("/rooms/trash", get(routes::rooms::trash))
+        .route("/rooms/{id}", patch(routes::rooms::update))
+        .route("/rooms/{id}/archive", post(routes::rooms::archive))
+        .route(
+            "/rooms/{id}/assignments",
+            get(routes::assignments::list).post(routes::assignments::create),
+        )
         .route("/rooms/{id}/delete", delete(routes::rooms::delete))
-        .route("/rooms/{id}/invitation-code", get(routes::rooms::invitation_code))
+        .route("/rooms/{id}/gradebook", get(routes::grades::gradebook))
+        .route(
+            "/rooms/{id}/invitation-code",
+            get(routes::rooms::invitation_code).delete(routes::rooms::revoke_invitation_code),
+        )
+        .route("/rooms/{id}/invitation-code/rotate", post(routes::rooms::rotate_invitation_code))
+        .route("/rooms/{id}/join-approval", put(routes::rooms::join_approval))
+        .route("/rooms/{id}/join-requests", get(routes::rooms::join_requests))
+        .route(
+            "/rooms/{id}/join-requests/{user_id}/approve",
+            post(routes::rooms::approve_join_request),
+        )
+        .route(
+            "/rooms/{id}/join-requests/{user_id}/reject",
+            post(routes::rooms::reject_join_request),
+        )
         .route("/rooms/{id}/leave", post(routes::rooms::leave))
-        .with_state(database)
+        .route("/rooms/{id}/members", get(routes::rooms::members))
+        .route("/rooms/{id}/members/{user_id}", delete(routes::rooms::remove_member))
+        .route("/rooms/{id}/members/{user_id}/role", put(routes::rooms::set_role))
+        .route("/rooms/{id}/restore", post(routes::rooms::restore))
+        .route("/rooms/{id}/transfer", post(routes::rooms::transfer))
+        .route("/rooms/{id}/unarchive", post(routes::rooms::unarchive))
+        .route("/submissions/{id}", get(routes::submissions::get))
+        .route("/submissions/{id}/files/{file_id}", get(routes::submissions::file))
+        .route(
+            "/submissions/{id}/grade",
+            get(routes::grades::get).put(routes::grades::grade),
+        )
+        .route("/users/me", get(routes::users::me).patch(routes::users::update_profile))
+        .route("/users/me/password", post(routes::users::change_password))
+        .route(
+            "/users/me/tokens",
+            get(routes::users::api_tokens).post(routes::users::create_api_token),
+        )
+        .route("/users/me/tokens/{id}", delete(routes::users::revoke_api_token))
+        .route(
+            "/users/me/totp",
+            post(routes::users::start_totp_enrollment).delete(routes::users::disable_totp),
+        )
+        .route("/users/me/totp/confirm", post(routes::users::confirm_totp_enrollment))
+        .with_state(state)
         .layer(CookieManagerLayer::new())
         .layer(cors_layer);
 
     let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+        .await
+        .unwrap();
 
     Ok(())
 }
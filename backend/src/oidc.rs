@@ -0,0 +1,203 @@
+use anyhow::{anyhow, Context, Result};
+use openidconnect::{
+    core::{CoreAuthenticationFlow, CoreClient, CoreProviderMetadata},
+    reqwest, AccessTokenHash, AuthorizationCode, ClaimsVerificationError, ClientId, ClientSecret, CsrfToken,
+    EndpointMaybeSet, EndpointNotSet, EndpointSet, IssuerUrl, Nonce, OAuth2TokenResponse,
+    PkceCodeChallenge, PkceCodeVerifier, RedirectUrl, Scope, TokenResponse,
+};
+use std::{env, sync::Arc};
+use tokio::sync::RwLock;
+
+use crate::types::OidcIdentity;
+
+const DEFAULT_REDIRECT_URL: &str = "http://localhost:3000/auth/oidc/callback";
+
+type DiscoveredClient =
+    CoreClient<EndpointSet, EndpointNotSet, EndpointNotSet, EndpointNotSet, EndpointMaybeSet, EndpointMaybeSet>;
+
+/// Where to send the user to log in, and what to remember until they come back.
+pub struct OidcAuthorization {
+    pub url: String,
+    /// Sent back by the identity provider as `state`
+    pub state: String,
+    pub pkce_verifier: String,
+    pub nonce: String,
+}
+
+/// Single sign-on through an OpenID Connect identity provider.
+pub struct Oidc {
+    issuer_url: IssuerUrl,
+    client_id: ClientId,
+    client_secret: Option<ClientSecret>,
+    redirect_url: RedirectUrl,
+    http: reqwest::Client,
+    /// Discovered on first use, so the server starts even while the provider is down
+    client: RwLock<Option<Arc<DiscoveredClient>>>,
+}
+
+impl Oidc {
+    /// Reads the OpenID Connect configuration from the environment.
+    ///
+    /// - `OIDC_ISSUER_URL`: issuer of the identity provider, single sign-on is off if unset
+    /// - `OIDC_CLIENT_ID`: client ID registered with the provider
+    /// - `OIDC_CLIENT_SECRET`: client secret, if the provider issued one
+    /// - `OIDC_REDIRECT_URL`: URL of `/auth/oidc/callback` as the provider sees it
+    ///   (default `http://localhost:3000/auth/oidc/callback`)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `OIDC_CLIENT_ID` is missing or a URL is invalid.
+    pub fn from_env() -> Result<Option<Self>> {
+        let Ok(issuer_url) = env::var("OIDC_ISSUER_URL") else {
+            return Ok(None);
+        };
+
+        let issuer_url = IssuerUrl::new(issuer_url).context("OIDC_ISSUER_URL is not a valid URL")?;
+        let client_id = env::var("OIDC_CLIENT_ID").context("OIDC_CLIENT_ID must be set along with OIDC_ISSUER_URL")?;
+        let client_secret = env::var("OIDC_CLIENT_SECRET").ok().map(ClientSecret::new);
+        let redirect_url = RedirectUrl::new(
+            env::var("OIDC_REDIRECT_URL").unwrap_or_else(|_| DEFAULT_REDIRECT_URL.to_string()),
+        )
+        .context("OIDC_REDIRECT_URL is not a valid URL")?;
+
+        let http = reqwest::Client::builder()
+            // Following redirects would let the provider point us at arbitrary hosts
+            .redirect(reqwest::redirect::Policy::none())
+            .build()?;
+
+        Ok(Some(Self {
+            issuer_url,
+            client_id: ClientId::new(client_id),
+            client_secret,
+            redirect_url,
+            http,
+            client: RwLock::new(None),
+        }))
+    }
+
+    async fn client(&self) -> Result<Arc<DiscoveredClient>> {
+        if let Some(client) = self.client.read().await.as_ref() {
+            return Ok(client.clone());
+        }
+
+        self.discover().await
+    }
+
+    /// Fetches the provider's endpoints and signing keys, again if the provider rotated its keys.
+    async fn discover(&self) -> Result<Arc<DiscoveredClient>> {
+        let metadata = CoreProviderMetadata::discover_async(self.issuer_url.clone(), &self.http)
+            .await
+            .map_err(|e| anyhow!("OIDC discovery failed: {e}"))?;
+
+        let client = Arc::new(
+            CoreClient::from_provider_metadata(metadata, self.client_id.clone(), self.client_secret.clone())
+                .set_redirect_uri(self.redirect_url.clone()),
+        );
+
+        *self.client.write().await = Some(client.clone());
+
+        Ok(client)
+    }
+
+    /// Builds the URL that sends the user to the identity provider, protected by PKCE,
+    /// a random `state` and a nonce the ID token has to carry.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the provider cannot be discovered.
+    pub async fn authorize(&self) -> Result<OidcAuthorization> {
+        let (pkce_challenge, pkce_verifier) = PkceCodeChallenge::new_random_sha256();
+
+        let (url, state, nonce) = self
+            .client()
+            .await?
+            .authorize_url(CoreAuthenticationFlow::AuthorizationCode, CsrfToken::new_random, Nonce::new_random)
+            .add_scope(Scope::new("email".to_string()))
+            .add_scope(Scope::new("profile".to_string()))
+            .set_pkce_challenge(pkce_challenge)
+            .url();
+
+        Ok(OidcAuthorization {
+            url: url.to_string(),
+            state: state.secret().clone(),
+            pkce_verifier: pkce_verifier.secret().clone(),
+            nonce: nonce.secret().clone(),
+        })
+    }
+
+    /// Redeems the code the provider sent back for an ID token and returns who it is for.
+    ///
+    /// The ID token's signature, issuer, audience, expiry and nonce are all checked.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the provider rejects the code or the ID token is missing or invalid.
+    pub async fn exchange(&self, code: String, pkce_verifier: String, nonce: String) -> Result<OidcIdentity> {
+        let mut client = self.client().await?;
+
+        let token_response = client
+            .exchange_code(AuthorizationCode::new(code))?
+            .set_pkce_verifier(PkceCodeVerifier::new(pkce_verifier))
+            .request_async(&self.http)
+            .await
+            .map_err(|e| anyhow!("OIDC code exchange failed: {e}"))?;
+
+        let id_token = token_response
+            .id_token()
+            .ok_or_else(|| anyhow!("OIDC provider did not return an ID token"))?;
+        let nonce = Nonce::new(nonce);
+
+        // A token signed with a key we have not seen means the keys were rotated since discovery
+        let keys_rotated = matches!(
+            id_token.claims(&client.id_token_verifier(), &nonce),
+            Err(ClaimsVerificationError::SignatureVerification(_))
+        );
+        if keys_rotated {
+            client = self.discover().await?;
+        }
+
+        let verifier = client.id_token_verifier();
+        let claims = id_token.claims(&verifier, &nonce)?;
+
+        // Make sure the access token was not swapped for one issued to someone else
+        if let Some(expected_hash) = claims.access_token_hash() {
+            let actual_hash = AccessTokenHash::from_token(
+                token_response.access_token(),
+                id_token.signing_alg()?,
+                id_token.signing_key(&verifier)?,
+            )?;
+
+            if actual_hash != *expected_hash {
+                return Err(anyhow!("OIDC access token hash does not match"));
+            }
+        }
+
+        let email = claims
+            .email()
+            .ok_or_else(|| anyhow!("OIDC provider did not share an email"))?
+            .to_string();
+
+        // Providers without separate name claims only send the full name
+        let full_name = claims.name().and_then(|name| name.get(None)).map(|name| name.to_string());
+        let name = claims
+            .given_name()
+            .and_then(|name| name.get(None))
+            .map(|name| name.to_string())
+            .or(full_name)
+            .unwrap_or_default();
+        let surname = claims
+            .family_name()
+            .and_then(|name| name.get(None))
+            .map(|name| name.to_string())
+            .unwrap_or_default();
+
+        Ok(OidcIdentity {
+            issuer: claims.issuer().to_string(),
+            subject: claims.subject().to_string(),
+            email,
+            email_verified: claims.email_verified().unwrap_or(false),
+            name,
+            surname,
+        })
+    }
+}
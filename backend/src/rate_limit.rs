@@ -0,0 +1,212 @@
+use axum::{
+    body::{self, Body},
+    extract::{ConnectInfo, FromRef, Request, State, Json},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::credential::Credential;
+use crate::data::{utils::normalize_email, Database};
+
+/// Above this many tracked keys, stale windows are dropped before counting a new hit,
+/// and if that is not enough the oldest windows go too.
+const MAX_TRACKED_KEYS: usize = 10_000;
+
+/// Largest request body [`limit_login`] and [`limit_recipient`] read to find the email in.
+const MAX_EMAIL_BODY_SIZE: usize = 16 * 1024;
+
+#[derive(Serialize)]
+pub enum RateLimitStatus {
+    TooManyRequests,
+}
+
+/// Counts requests per key in fixed time windows, kept in memory.
+pub struct RateLimiter {
+    limit: u32,
+    window: Duration,
+    hits: Mutex<HashMap<String, (Instant, u32)>>,
+}
+
+impl RateLimiter {
+    /// Allows `limit` requests per key in every `window`.
+    pub fn new(limit: u32, window: Duration) -> Self {
+        Self {
+            limit,
+            window,
+            hits: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Counts a request for `key`, returning how long to wait if it is over the limit.
+    pub fn check(&self, key: &str) -> Result<(), Duration> {
+        let now = Instant::now();
+        let mut hits = self.hits.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+
+        if hits.len() >= MAX_TRACKED_KEYS && !hits.contains_key(key) {
+            hits.retain(|_, (started, _)| now.duration_since(*started) < self.window);
+
+            // Many clients at once, which forgets the ones that have been around the longest
+            // rather than letting the map grow without bound
+            while hits.len() >= MAX_TRACKED_KEYS {
+                let Some(oldest) = hits.iter().min_by_key(|(_, (started, _))| *started).map(|(key, _)| key.clone()) else {
+                    break;
+                };
+                hits.remove(&oldest);
+            }
+        }
+
+        let (started, count) = hits.entry(key.to_string()).or_insert((now, 0));
+        if now.duration_since(*started) >= self.window {
+            *started = now;
+            *count = 0;
+        }
+
+        if *count >= self.limit {
+            return Err(self.window.saturating_sub(now.duration_since(*started)));
+        }
+
+        *count += 1;
+
+        Ok(())
+    }
+}
+
+/// State of the [`limit`] middleware, which needs the database to tell who is logged in.
+#[derive(Clone, FromRef)]
+pub struct RateLimitState {
+    pub limiter: Arc<RateLimiter>,
+    pub db: Database,
+}
+
+/// Builds a `429 Too Many Requests` response telling the client when to try again.
+pub fn too_many_requests<T: Serialize>(retry_after: Duration, body: T) -> Response {
+    // Round up, a client retrying a moment too early would just be limited again
+    let seconds = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        [(header::RETRY_AFTER, seconds.to_string())],
+        Json(body),
+    )
+        .into_response()
+}
+
+/// Middleware limiting requests by client IP and, for logged in clients, by user.
+///
+/// Meant to be added to single routes, e.g.
+/// `.route_layer(middleware::from_fn_with_state(state, rate_limit::limit))`.
+/// Credentials that do not belong to anyone are only limited by IP, since a client can make
+/// up as many of them as it likes. Behind a reverse proxy every client shares the proxy's IP,
+/// so limits should be set with that in mind.
+pub async fn limit(
+    State(limiter): State<Arc<RateLimiter>>,
+    State(db): State<Database>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Credential(credential): Credential,
+    request: Request,
+    next: Next,
+) -> Response {
+    if let Err(retry_after) = limiter.check(&format!("ip:{}", addr.ip())) {
+        return too_many_requests(retry_after, RateLimitStatus::TooManyRequests);
+    }
+
+    let user_id = match credential {
+        Some(credential) => match db.credential_user_id(credential).await {
+            Ok(user_id) => user_id,
+            Err(e) => {
+                eprintln!("Rate limit user lookup error: {e}");
+                None
+            }
+        },
+        None => None,
+    };
+
+    if let Some(user_id) = user_id
+        && let Err(retry_after) = limiter.check(&format!("user:{user_id}"))
+    {
+        return too_many_requests(retry_after, RateLimitStatus::TooManyRequests);
+    }
+
+    next.run(request).await
+}
+
+#[derive(Deserialize)]
+struct EmailBody {
+    email: String,
+}
+
+/// Reads the normalized email out of a JSON request body, handing back an equal request to pass on.
+///
+/// Bodies that are not JSON or have no email give `None`, the handler rejects those itself.
+async fn body_email(request: Request) -> Result<(Request, Option<String>), Response> {
+    let (parts, body) = request.into_parts();
+    let Ok(bytes) = body::to_bytes(body, MAX_EMAIL_BODY_SIZE).await else {
+        return Err(StatusCode::PAYLOAD_TOO_LARGE.into_response());
+    };
+
+    let email = serde_json::from_slice::<EmailBody>(&bytes)
+        .ok()
+        .map(|body| normalize_email(&body.email));
+
+    Ok((Request::from_parts(parts, Body::from(bytes)), email))
+}
+
+/// Middleware limiting login attempts by client IP and by the email they are made for.
+///
+/// Unlike [`limit`] this does not count per logged in user, since whoever is logging in is
+/// not logged in yet. Requests without an email in their JSON body, like completing a login
+/// with a TOTP code, are only limited by IP.
+pub async fn limit_login(
+    State(limiter): State<Arc<RateLimiter>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if let Err(retry_after) = limiter.check(&format!("ip:{}", addr.ip())) {
+        return too_many_requests(retry_after, RateLimitStatus::TooManyRequests);
+    }
+
+    let (request, email) = match body_email(request).await {
+        Ok(read) => read,
+        Err(response) => return response,
+    };
+
+    if let Some(email) = email
+        && let Err(retry_after) = limiter.check(&format!("email:{email}"))
+    {
+        return too_many_requests(retry_after, RateLimitStatus::TooManyRequests);
+    }
+
+    next.run(request).await
+}
+
+/// Middleware limiting requests by the email in their JSON body, i.e. the mail recipient.
+///
+/// Meant to sit next to a per IP [`limit`] on routes that send mail, so one inbox cannot be
+/// flooded from many addresses while many users behind one address are not throttled together.
+pub async fn limit_recipient(
+    State(limiter): State<Arc<RateLimiter>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let (request, email) = match body_email(request).await {
+        Ok(read) => read,
+        Err(response) => return response,
+    };
+
+    if let Some(email) = email
+        && let Err(retry_after) = limiter.check(&format!("email:{email}"))
+    {
+        return too_many_requests(retry_after, RateLimitStatus::TooManyRequests);
+    }
+
+    next.run(request).await
+}
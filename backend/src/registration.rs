@@ -0,0 +1,27 @@
+use std::env;
+
+#[derive(Clone)]
+pub struct RegistrationConfig {
+    /// Answer a registration with a taken email like a successful one, and tell the
+    /// owner of the email by mail instead
+    pub hide_existing_emails: bool,
+}
+
+impl RegistrationConfig {
+    /// Reads the registration configuration from the environment.
+    ///
+    /// - `REGISTRATION_CONFLICTS`: one of `reveal` (default), which answers a taken email with
+    ///   `409 Conflict`, or `hide`, which does not let registration tell which emails are in use
+    pub fn from_env() -> Self {
+        let hide_existing_emails = match env::var("REGISTRATION_CONFLICTS").as_deref() {
+            Ok("hide") => true,
+            Ok("reveal") | Err(_) => false,
+            Ok(other) => {
+                println!("WARN: unknown REGISTRATION_CONFLICTS {other:?}, revealing taken emails");
+                false
+            }
+        };
+
+        Self { hide_existing_emails }
+    }
+}
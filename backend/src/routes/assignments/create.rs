@@ -0,0 +1,43 @@
+use axum::{
+    extract::{Path, State, Json},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+
+use crate::credential::Credential;
+use crate::data::{Database, CreateAssignmentOutcome};
+use crate::types::NewAssignment;
+
+#[derive(Serialize)]
+pub enum CreateAssignmentStatus {
+    Success(i32),
+    InternalServerError,
+    NotLoggedIn,
+    NotPermitted,
+    RoomArchived,
+    InvalidAssignment,
+}
+
+pub async fn create(
+    State(db): State<Database>,
+    Credential(credential): Credential,
+    Path(room_id): Path<i32>,
+    Json(assignment): Json<NewAssignment>,
+) -> impl IntoResponse {
+    if let Some(credential) = credential {
+        match db.create_assignment(credential, room_id, assignment).await {
+            Ok(CreateAssignmentOutcome::Success(id)) => (StatusCode::OK, Json(CreateAssignmentStatus::Success(id))),
+            Ok(CreateAssignmentOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(CreateAssignmentStatus::NotLoggedIn)),
+            Ok(CreateAssignmentOutcome::NotPermitted) => (StatusCode::UNAUTHORIZED, Json(CreateAssignmentStatus::NotPermitted)),
+            Ok(CreateAssignmentOutcome::RoomArchived) => (StatusCode::CONFLICT, Json(CreateAssignmentStatus::RoomArchived)),
+            Ok(CreateAssignmentOutcome::InvalidAssignment) => (StatusCode::BAD_REQUEST, Json(CreateAssignmentStatus::InvalidAssignment)),
+            Err(e) => {
+                eprintln!("Create assignment error: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(CreateAssignmentStatus::InternalServerError))
+            }
+        }
+    } else {
+        (StatusCode::UNAUTHORIZED, Json(CreateAssignmentStatus::NotLoggedIn))
+    }
+}
@@ -0,0 +1,38 @@
+use axum::{
+    extract::{Path, State, Json},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+
+use crate::credential::Credential;
+use crate::data::{Database, GetAssignmentOutcome};
+use crate::types::Assignment;
+
+#[derive(Serialize)]
+pub enum GetAssignmentStatus {
+    Success(Assignment),
+    InternalServerError,
+    NotLoggedIn,
+    NotFound,
+}
+
+pub async fn get(
+    State(db): State<Database>,
+    Credential(credential): Credential,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    if let Some(credential) = credential {
+        match db.get_assignment(credential, id).await {
+            Ok(GetAssignmentOutcome::Success(assignment)) => (StatusCode::OK, Json(GetAssignmentStatus::Success(assignment))),
+            Ok(GetAssignmentOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(GetAssignmentStatus::NotLoggedIn)),
+            Ok(GetAssignmentOutcome::NotFound) => (StatusCode::NOT_FOUND, Json(GetAssignmentStatus::NotFound)),
+            Err(e) => {
+                eprintln!("Get assignment error: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(GetAssignmentStatus::InternalServerError))
+            }
+        }
+    } else {
+        (StatusCode::UNAUTHORIZED, Json(GetAssignmentStatus::NotLoggedIn))
+    }
+}
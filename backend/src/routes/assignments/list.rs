@@ -0,0 +1,38 @@
+use axum::{
+    extract::{Path, State, Json},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+
+use crate::credential::Credential;
+use crate::data::{Database, ListAssignmentsOutcome};
+use crate::types::Assignment;
+
+#[derive(Serialize)]
+pub enum ListAssignmentsStatus {
+    Success(Vec<Assignment>),
+    InternalServerError,
+    NotLoggedIn,
+    NotMember,
+}
+
+pub async fn list(
+    State(db): State<Database>,
+    Credential(credential): Credential,
+    Path(room_id): Path<i32>,
+) -> impl IntoResponse {
+    if let Some(credential) = credential {
+        match db.list_assignments(credential, room_id).await {
+            Ok(ListAssignmentsOutcome::Success(assignments)) => (StatusCode::OK, Json(ListAssignmentsStatus::Success(assignments))),
+            Ok(ListAssignmentsOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(ListAssignmentsStatus::NotLoggedIn)),
+            Ok(ListAssignmentsOutcome::NotMember) => (StatusCode::UNAUTHORIZED, Json(ListAssignmentsStatus::NotMember)),
+            Err(e) => {
+                eprintln!("List assignments error: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(ListAssignmentsStatus::InternalServerError))
+            }
+        }
+    } else {
+        (StatusCode::UNAUTHORIZED, Json(ListAssignmentsStatus::NotLoggedIn))
+    }
+}
@@ -0,0 +1,8 @@
+mod create;
+pub use create::create;
+
+mod get;
+pub use get::get;
+
+mod list;
+pub use list::list;
@@ -0,0 +1,14 @@
+use tower_cookies::{cookie::time::Duration, Cookie};
+
+use crate::data::SESSION_TTL;
+
+/// Builds the `session_uuid` cookie, it lives as long as an unused session does.
+pub fn session_cookie(session_uuid: String) -> Cookie<'static> {
+    Cookie::build(("session_uuid", session_uuid))
+        .path("/")
+        .http_only(true)
+        .max_age(Duration::seconds(SESSION_TTL))
+        // we don't run server/frontend over https yet
+        //.secure(true); // only over HTTPS
+        .into()
+}
@@ -7,6 +7,7 @@ use serde::Serialize;
 use tower_cookies::Cookies;
 
 use crate::data::Database;
+use super::cookie::session_cookie;
 
 #[derive(Serialize)]
 enum LoggedInStatus {
@@ -21,6 +22,8 @@ pub async fn is_logged_in(
     let status = match cookies.get("session_uuid") {
         Some(c) => {
             if db.verify_session(c.value().to_string()).await.unwrap_or(false) {
+                // the session was just extended, so extend the cookie with it
+                cookies.add(session_cookie(c.value().to_string()));
                 LoggedInStatus::LoggedIn
             } else {
                 LoggedInStatus::LoggedOut
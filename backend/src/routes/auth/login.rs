@@ -1,18 +1,25 @@
 use axum::{
-    extract::{State, Json},
-    http::StatusCode,
-    response::IntoResponse,
+    extract::{ConnectInfo, State, Json},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
 };
+use std::{net::SocketAddr, sync::Arc, time::Duration};
 use serde::{Deserialize, Serialize};
-use tower_cookies::{Cookies, Cookie};
+use tower_cookies::Cookies;
 
 use crate::data::{Database, LoginOutcome};
+use crate::rate_limit::too_many_requests;
+use crate::verification::EmailVerification;
+use super::cookie::session_cookie;
 
 #[derive(Serialize)]
 enum LoginStatus {
     Success,
-    UserDoesNotExist,
+    /// The challenge to send to `/auth/login/totp` along with a TOTP code
+    SecondFactorRequired(String),
     InvalidCredentials,
+    EmailNotVerified,
+    AccountLocked,
     InternalServerError,
 }
 
@@ -22,26 +29,41 @@ pub struct LoginRequest {
     pub password: String,
 }
 
+/// Reads the `User-Agent` header, which is stored with new sessions.
+pub(super) fn user_agent(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(header::USER_AGENT)
+        .and_then(|value| value.to_str().ok())
+        .map(ToString::to_string)
+}
+
 pub async fn login(
     State(db): State<Database>,
+    State(verification): State<Arc<EmailVerification>>,
     cookies: Cookies,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Json(user): Json<LoginRequest>,
-) -> impl IntoResponse {
-    match db.login_user(user.email, user.password).await {
+) -> Response {
+    let require_verified_email = !verification.may_log_in_unverified();
+
+    match db
+        .login_user(user.email, user.password, user_agent(&headers), Some(addr.ip().to_string()), require_verified_email)
+        .await
+    {
         Ok(LoginOutcome::Success(session_uuid)) => {
             // set the cookie
-            let c = Cookie::build(("session_uuid", session_uuid))
-                .path("/")
-                .http_only(true);
-                // we don't run server/frontend over https yet
-                //.secure(true); // only over HTTPS
+            cookies.add(session_cookie(session_uuid));
 
-            cookies.add(c.into());
-
-            (StatusCode::OK, Json(LoginStatus::Success))
+            (StatusCode::OK, Json(LoginStatus::Success)).into_response()
+        }
+        Ok(LoginOutcome::SecondFactorRequired(challenge)) => (StatusCode::OK, Json(LoginStatus::SecondFactorRequired(challenge))).into_response(),
+        Ok(LoginOutcome::InvalidCredentials) => (StatusCode::UNAUTHORIZED, Json(LoginStatus::InvalidCredentials)).into_response(),
+        Ok(LoginOutcome::EmailNotVerified) => (StatusCode::FORBIDDEN, Json(LoginStatus::EmailNotVerified)).into_response(),
+        Ok(LoginOutcome::AccountLocked(seconds)) => {
+            let retry_after = Duration::from_secs(u64::try_from(seconds).unwrap_or(0));
+            too_many_requests(retry_after, LoginStatus::AccountLocked)
         }
-        Ok(LoginOutcome::UserDoesNotExist) => (StatusCode::NOT_ACCEPTABLE, Json(LoginStatus::UserDoesNotExist)),
-        Ok(LoginOutcome::InvalidCredentials) => (StatusCode::UNAUTHORIZED, Json(LoginStatus::InvalidCredentials)),
-        Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, Json(LoginStatus::InternalServerError)),
+        Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, Json(LoginStatus::InternalServerError)).into_response(),
     }
 }
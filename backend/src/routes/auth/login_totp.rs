@@ -0,0 +1,46 @@
+use axum::{
+    extract::{ConnectInfo, State, Json},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+};
+use serde::Serialize;
+use std::net::SocketAddr;
+use tower_cookies::Cookies;
+
+use crate::data::{CompleteTotpLoginOutcome, Database};
+use crate::types::TotpLogin;
+use super::cookie::session_cookie;
+use super::login::user_agent;
+
+#[derive(Serialize)]
+enum LoginTotpStatus {
+    Success,
+    InvalidChallenge,
+    InvalidCode,
+    InternalServerError,
+}
+
+pub async fn login_totp(
+    State(db): State<Database>,
+    cookies: Cookies,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(login): Json<TotpLogin>,
+) -> impl IntoResponse {
+    match db
+        .complete_totp_login(login.challenge, login.code, user_agent(&headers), Some(addr.ip().to_string()))
+        .await
+    {
+        Ok(CompleteTotpLoginOutcome::Success(session_uuid)) => {
+            cookies.add(session_cookie(session_uuid));
+
+            (StatusCode::OK, Json(LoginTotpStatus::Success))
+        }
+        Ok(CompleteTotpLoginOutcome::InvalidChallenge) => (StatusCode::UNAUTHORIZED, Json(LoginTotpStatus::InvalidChallenge)),
+        Ok(CompleteTotpLoginOutcome::InvalidCode) => (StatusCode::UNAUTHORIZED, Json(LoginTotpStatus::InvalidCode)),
+        Err(e) => {
+            eprintln!("TOTP login error: {e}");
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(LoginTotpStatus::InternalServerError))
+        }
+    }
+}
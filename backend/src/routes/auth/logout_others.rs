@@ -0,0 +1,35 @@
+use axum::{
+    extract::{State, Json},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+use tower_cookies::Cookies;
+
+use crate::data::{Database, RevokeOtherSessionsOutcome};
+
+#[derive(Serialize)]
+pub enum LogoutOthersStatus {
+    /// Number of sessions that were logged out
+    Success(usize),
+    InternalServerError,
+    NotLoggedIn,
+}
+
+pub async fn logout_others(
+    State(db): State<Database>,
+    cookies: Cookies,
+) -> impl IntoResponse {
+    if let Some(session_uuid_cookie) = cookies.get("session_uuid") {
+        match db.revoke_other_sessions(session_uuid_cookie.value().to_string()).await {
+            Ok(RevokeOtherSessionsOutcome::Success(count)) => (StatusCode::OK, Json(LogoutOthersStatus::Success(count))),
+            Ok(RevokeOtherSessionsOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(LogoutOthersStatus::NotLoggedIn)),
+            Err(e) => {
+                eprintln!("Log out other sessions error: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(LogoutOthersStatus::InternalServerError))
+            }
+        }
+    } else {
+        (StatusCode::UNAUTHORIZED, Json(LogoutOthersStatus::NotLoggedIn))
+    }
+}
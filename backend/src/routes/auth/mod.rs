@@ -1,11 +1,34 @@
+mod cookie;
+
 mod is_logged_in;
 pub use is_logged_in::is_logged_in;
 
 mod login;
 pub use login::login;
 
+mod login_totp;
+pub use login_totp::login_totp;
+
 mod logout;
 pub use logout::logout;
 
+mod logout_others;
+pub use logout_others::logout_others;
+
+mod oidc;
+pub use oidc::{oidc_callback, oidc_login};
+
+mod password_reset;
+pub use password_reset::{confirm_password_reset, request_password_reset};
+
 mod register;
 pub use register::register;
+
+mod revoke_session;
+pub use revoke_session::revoke_session;
+
+mod sessions;
+pub use sessions::sessions;
+
+mod verify_email;
+pub use verify_email::{resend_verification_email, send_verification_email, verify_email};
@@ -0,0 +1,163 @@
+use axum::{
+    extract::{ConnectInfo, Query, State, Json},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Redirect, Response},
+};
+use serde::{Deserialize, Serialize};
+use std::{net::SocketAddr, sync::Arc};
+use tower_cookies::{cookie::{time::Duration, SameSite}, Cookie, Cookies};
+
+use crate::data::{Database, OidcLoginOutcome};
+use crate::mailer::Mailer;
+use crate::oidc::Oidc;
+use crate::verification::EmailVerification;
+use super::cookie::session_cookie;
+use super::login::user_agent;
+
+/// Ties the callback to the browser that started the login.
+const STATE_COOKIE: &str = "oidc_state";
+
+#[derive(Serialize)]
+enum OidcStatus {
+    NotConfigured,
+}
+
+/// Why a single sign-on login failed, passed to the frontend as `?error=`.
+#[derive(Debug)]
+enum OidcError {
+    Cancelled,
+    InvalidState,
+    EmailNotVerified,
+    EmailInUse,
+    InternalServerError,
+}
+
+#[derive(Deserialize)]
+pub struct OidcCallbackQuery {
+    code: Option<String>,
+    state: Option<String>,
+    /// Set by the provider instead of `code` if the user did not log in
+    error: Option<String>,
+}
+
+fn state_cookie(state: String) -> Cookie<'static> {
+    Cookie::build((STATE_COOKIE, state))
+        .path("/auth/oidc")
+        .http_only(true)
+        // Lax still sends it along when the provider redirects back
+        .same_site(SameSite::Lax)
+        .max_age(Duration::minutes(10))
+        .into()
+}
+
+/// Sends the browser back to the frontend, to its login page if something went wrong.
+fn back_to_app(mailer: &Mailer, error: Option<OidcError>) -> Response {
+    match error {
+        None => Redirect::to(&format!("{}/", mailer.app_url)).into_response(),
+        Some(error) => Redirect::to(&format!("{}/login?error={error:?}", mailer.app_url)).into_response(),
+    }
+}
+
+/// Starts a single sign-on login by redirecting to the identity provider.
+pub async fn oidc_login(
+    State(db): State<Database>,
+    State(oidc): State<Option<Arc<Oidc>>>,
+    State(mailer): State<Arc<Mailer>>,
+    cookies: Cookies,
+) -> Response {
+    let Some(oidc) = oidc else {
+        return (StatusCode::NOT_FOUND, Json(OidcStatus::NotConfigured)).into_response();
+    };
+
+    let authorization = match oidc.authorize().await {
+        Ok(authorization) => authorization,
+        Err(e) => {
+            eprintln!("OIDC login error: {e}");
+            return back_to_app(&mailer, Some(OidcError::InternalServerError));
+        }
+    };
+
+    let state = authorization.state.clone();
+    if let Err(e) = db
+        .start_oidc_login(authorization.state, authorization.pkce_verifier, authorization.nonce)
+        .await
+    {
+        eprintln!("OIDC login error: {e}");
+        return back_to_app(&mailer, Some(OidcError::InternalServerError));
+    }
+
+    cookies.add(state_cookie(state));
+
+    Redirect::to(&authorization.url).into_response()
+}
+
+/// Where the identity provider sends the user back to, logs them in and returns to the frontend.
+#[allow(clippy::too_many_arguments)]
+pub async fn oidc_callback(
+    State(db): State<Database>,
+    State(oidc): State<Option<Arc<Oidc>>>,
+    State(mailer): State<Arc<Mailer>>,
+    State(verification): State<Arc<EmailVerification>>,
+    cookies: Cookies,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Query(query): Query<OidcCallbackQuery>,
+) -> Response {
+    let Some(oidc) = oidc else {
+        return (StatusCode::NOT_FOUND, Json(OidcStatus::NotConfigured)).into_response();
+    };
+
+    let expected_state = cookies.get(STATE_COOKIE).map(|cookie| cookie.value().to_string());
+    cookies.remove(state_cookie(String::new()));
+
+    if query.error.is_some() {
+        return back_to_app(&mailer, Some(OidcError::Cancelled));
+    }
+
+    let (Some(code), Some(state)) = (query.code, query.state) else {
+        return back_to_app(&mailer, Some(OidcError::InvalidState));
+    };
+
+    if expected_state.as_deref() != Some(state.as_str()) {
+        return back_to_app(&mailer, Some(OidcError::InvalidState));
+    }
+
+    let (pkce_verifier, nonce) = match db.take_oidc_login(state).await {
+        Ok(Some(login)) => login,
+        Ok(None) => return back_to_app(&mailer, Some(OidcError::InvalidState)),
+        Err(e) => {
+            eprintln!("OIDC callback error: {e}");
+            return back_to_app(&mailer, Some(OidcError::InternalServerError));
+        }
+    };
+
+    let identity = match oidc.exchange(code, pkce_verifier, nonce).await {
+        Ok(identity) => identity,
+        Err(e) => {
+            eprintln!("OIDC callback error: {e}");
+            return back_to_app(&mailer, Some(OidcError::InternalServerError));
+        }
+    };
+
+    let require_verified_email = !verification.may_log_in_unverified();
+
+    match db
+        .oidc_login(identity, user_agent(&headers), Some(addr.ip().to_string()), require_verified_email)
+        .await
+    {
+        Ok(OidcLoginOutcome::Success(session_uuid)) => {
+            cookies.add(session_cookie(session_uuid));
+            back_to_app(&mailer, None)
+        }
+        // In the fragment, so the challenge is not sent along to any server or logged
+        Ok(OidcLoginOutcome::SecondFactorRequired(challenge)) => {
+            Redirect::to(&format!("{}/login#challenge={challenge}", mailer.app_url)).into_response()
+        }
+        Ok(OidcLoginOutcome::EmailNotVerified) => back_to_app(&mailer, Some(OidcError::EmailNotVerified)),
+        Ok(OidcLoginOutcome::EmailInUse) => back_to_app(&mailer, Some(OidcError::EmailInUse)),
+        Err(e) => {
+            eprintln!("OIDC callback error: {e}");
+            back_to_app(&mailer, Some(OidcError::InternalServerError))
+        }
+    }
+}
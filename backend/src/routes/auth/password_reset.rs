@@ -0,0 +1,78 @@
+use axum::{
+    extract::{State, Json},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::data::{ConfirmPasswordResetOutcome, Database, RequestPasswordResetOutcome};
+use crate::mailer::{Email, Mailer};
+use crate::types::{PasswordResetConfirmation, PasswordResetRequest};
+
+#[derive(Serialize)]
+pub enum RequestPasswordResetStatus {
+    Success,
+    InternalServerError,
+}
+
+#[derive(Serialize)]
+pub enum ConfirmPasswordResetStatus {
+    Success,
+    InvalidToken,
+    InternalServerError,
+}
+
+/// Emails a password reset link if the address belongs to a user.
+///
+/// The response is the same whether or not the user exists, so this cannot be used to
+/// find out which emails are registered.
+pub async fn request_password_reset(
+    State(db): State<Database>,
+    State(mailer): State<Arc<Mailer>>,
+    Json(request): Json<PasswordResetRequest>,
+) -> impl IntoResponse {
+    match db.request_password_reset(request.email.clone()).await {
+        Ok(RequestPasswordResetOutcome::Success(token)) => {
+            let email = Email {
+                to: request.email,
+                subject: "Reset your password".to_string(),
+                body: format!(
+                    "Someone asked to reset the password of your account.\n\n\
+                    To choose a new password, open this link within the next hour:\n\
+                    {}/reset-password?token={token}\n\n\
+                    If this was not you, you can ignore this email.",
+                    mailer.app_url,
+                ),
+            };
+
+            // Sending in the background keeps the response time the same for unknown emails
+            tokio::task::spawn_blocking(move || {
+                if let Err(e) = mailer.send(&email) {
+                    eprintln!("Password reset mail error: {e}");
+                }
+            });
+
+            (StatusCode::OK, Json(RequestPasswordResetStatus::Success))
+        }
+        Ok(RequestPasswordResetOutcome::UserDoesNotExist) => (StatusCode::OK, Json(RequestPasswordResetStatus::Success)),
+        Err(e) => {
+            eprintln!("Request password reset error: {e}");
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(RequestPasswordResetStatus::InternalServerError))
+        }
+    }
+}
+
+pub async fn confirm_password_reset(
+    State(db): State<Database>,
+    Json(confirmation): Json<PasswordResetConfirmation>,
+) -> impl IntoResponse {
+    match db.confirm_password_reset(confirmation.token, confirmation.password).await {
+        Ok(ConfirmPasswordResetOutcome::Success) => (StatusCode::OK, Json(ConfirmPasswordResetStatus::Success)),
+        Ok(ConfirmPasswordResetOutcome::InvalidToken) => (StatusCode::BAD_REQUEST, Json(ConfirmPasswordResetStatus::InvalidToken)),
+        Err(e) => {
+            eprintln!("Confirm password reset error: {e}");
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(ConfirmPasswordResetStatus::InternalServerError))
+        }
+    }
+}
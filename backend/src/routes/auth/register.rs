@@ -4,9 +4,14 @@ use axum::{
     response::IntoResponse,
 };
 use serde::Serialize;
+use std::sync::Arc;
 
 use crate::data::{Database, RegisterOutcome};
+use crate::mailer::{Email, Mailer};
+use crate::registration::RegistrationConfig;
 use crate::types::NewUser;
+use crate::verification::EmailVerification;
+use super::verify_email::send_verification_email;
 
 #[derive(Serialize)]
 pub enum RegisterStatus {
@@ -15,12 +20,45 @@ pub enum RegisterStatus {
     InternalServerError,
 }
 
+/// Tells the owner of an email that someone tried to register with it, in the background.
+fn send_already_registered_email(mailer: Arc<Mailer>, email: String) {
+    let email = Email {
+        to: email,
+        subject: "You already have an account".to_string(),
+        body: format!(
+            "Someone tried to sign up with this email, but it already belongs to an account.\n\
+            If that was you, log in instead, or request a password reset if you forgot it:\n\
+            {}\n\n\
+            If it was not you, you can ignore this email.",
+            mailer.app_url,
+        ),
+    };
+
+    tokio::task::spawn_blocking(move || {
+        if let Err(e) = mailer.send(&email) {
+            eprintln!("Already registered mail error: {e}");
+        }
+    });
+}
+
 pub async fn register(
     State(db): State<Database>,
+    State(mailer): State<Arc<Mailer>>,
+    State(verification): State<Arc<EmailVerification>>,
+    State(registration): State<Arc<RegistrationConfig>>,
     Json(user): Json<NewUser>,
 ) -> impl IntoResponse {
+    let email = user.email.clone();
+
     match db.register_user(user).await {
-        Ok(RegisterOutcome::Success) => (StatusCode::OK, Json(RegisterStatus::Success)),
+        Ok(RegisterOutcome::Success(user_id)) => {
+            send_verification_email(mailer, &verification, user_id, email);
+            (StatusCode::OK, Json(RegisterStatus::Success))
+        }
+        Ok(RegisterOutcome::UserAlreadyExists) if registration.hide_existing_emails => {
+            send_already_registered_email(mailer, email);
+            (StatusCode::OK, Json(RegisterStatus::Success))
+        }
         Ok(RegisterOutcome::UserAlreadyExists) => (StatusCode::CONFLICT, Json(RegisterStatus::UserAlreadyExists)),
         Err(e) => {
             eprintln!("Register error: {e}");
@@ -0,0 +1,37 @@
+use axum::{
+    extract::{Path, State, Json},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+use tower_cookies::Cookies;
+
+use crate::data::{Database, RevokeSessionOutcome};
+
+#[derive(Serialize)]
+pub enum RevokeSessionStatus {
+    Success,
+    InternalServerError,
+    NotLoggedIn,
+    SessionNotFound,
+}
+
+pub async fn revoke_session(
+    State(db): State<Database>,
+    cookies: Cookies,
+    Path(id): Path<i64>,
+) -> impl IntoResponse {
+    if let Some(session_uuid_cookie) = cookies.get("session_uuid") {
+        match db.revoke_session(session_uuid_cookie.value().to_string(), id).await {
+            Ok(RevokeSessionOutcome::Success) => (StatusCode::OK, Json(RevokeSessionStatus::Success)),
+            Ok(RevokeSessionOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(RevokeSessionStatus::NotLoggedIn)),
+            Ok(RevokeSessionOutcome::SessionNotFound) => (StatusCode::NOT_FOUND, Json(RevokeSessionStatus::SessionNotFound)),
+            Err(e) => {
+                eprintln!("Revoke session error: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(RevokeSessionStatus::InternalServerError))
+            }
+        }
+    } else {
+        (StatusCode::UNAUTHORIZED, Json(RevokeSessionStatus::NotLoggedIn))
+    }
+}
@@ -0,0 +1,35 @@
+use axum::{
+    extract::{State, Json},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+use tower_cookies::Cookies;
+
+use crate::data::{Database, ListSessionsOutcome};
+use crate::types::Session;
+
+#[derive(Serialize)]
+pub enum ListSessionsStatus {
+    Success(Vec<Session>),
+    InternalServerError,
+    NotLoggedIn,
+}
+
+pub async fn sessions(
+    State(db): State<Database>,
+    cookies: Cookies,
+) -> impl IntoResponse {
+    if let Some(session_uuid_cookie) = cookies.get("session_uuid") {
+        match db.list_sessions(session_uuid_cookie.value().to_string()).await {
+            Ok(ListSessionsOutcome::Success(sessions)) => (StatusCode::OK, Json(ListSessionsStatus::Success(sessions))),
+            Ok(ListSessionsOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(ListSessionsStatus::NotLoggedIn)),
+            Err(e) => {
+                eprintln!("List sessions error: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(ListSessionsStatus::InternalServerError))
+            }
+        }
+    } else {
+        (StatusCode::UNAUTHORIZED, Json(ListSessionsStatus::NotLoggedIn))
+    }
+}
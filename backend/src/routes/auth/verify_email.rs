@@ -0,0 +1,95 @@
+use axum::{
+    extract::{Path, State, Json},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::data::{Database, VerifyEmailOutcome};
+use crate::mailer::{Email, Mailer};
+use crate::types::ResendVerificationEmail;
+use crate::verification::EmailVerification;
+
+#[derive(Serialize)]
+pub enum VerifyEmailStatus {
+    Success,
+    AlreadyVerified,
+    InvalidToken,
+    InternalServerError,
+}
+
+#[derive(Serialize)]
+pub enum ResendVerificationEmailStatus {
+    Success,
+    InternalServerError,
+}
+
+/// Sends a verification link for `email` in the background.
+pub fn send_verification_email(
+    mailer: Arc<Mailer>,
+    verification: &EmailVerification,
+    user_id: i64,
+    email: String,
+) {
+    let token = verification.token(user_id, &email);
+    let email = Email {
+        to: email,
+        subject: "Verify your email".to_string(),
+        body: format!(
+            "To confirm that this is your email, open this link within the next week:\n\
+            {}/verify-email?token={token}\n\n\
+            If you did not sign up or change your email, you can ignore this email.",
+            mailer.app_url,
+        ),
+    };
+
+    tokio::task::spawn_blocking(move || {
+        if let Err(e) = mailer.send(&email) {
+            eprintln!("Verification mail error: {e}");
+        }
+    });
+}
+
+pub async fn verify_email(
+    State(db): State<Database>,
+    State(verification): State<Arc<EmailVerification>>,
+    Path(token): Path<String>,
+) -> impl IntoResponse {
+    let Some((user_id, email)) = verification.check(&token) else {
+        return (StatusCode::BAD_REQUEST, Json(VerifyEmailStatus::InvalidToken));
+    };
+
+    match db.verify_email(user_id, email).await {
+        Ok(VerifyEmailOutcome::Success) => (StatusCode::OK, Json(VerifyEmailStatus::Success)),
+        Ok(VerifyEmailOutcome::AlreadyVerified) => (StatusCode::OK, Json(VerifyEmailStatus::AlreadyVerified)),
+        Ok(VerifyEmailOutcome::EmailChanged) => (StatusCode::BAD_REQUEST, Json(VerifyEmailStatus::InvalidToken)),
+        Err(e) => {
+            eprintln!("Verify email error: {e}");
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(VerifyEmailStatus::InternalServerError))
+        }
+    }
+}
+
+/// Sends a new verification link if the address belongs to a user who has not verified it yet.
+///
+/// Like a password reset request, the response does not reveal whether the user exists.
+pub async fn resend_verification_email(
+    State(db): State<Database>,
+    State(mailer): State<Arc<Mailer>>,
+    State(verification): State<Arc<EmailVerification>>,
+    Json(request): Json<ResendVerificationEmail>,
+) -> impl IntoResponse {
+    match db.unverified_user_id(request.email.clone()).await {
+        Ok(user_id) => {
+            if let Some(user_id) = user_id {
+                send_verification_email(mailer, &verification, user_id, request.email);
+            }
+            (StatusCode::OK, Json(ResendVerificationEmailStatus::Success))
+        }
+        Err(e) => {
+            eprintln!("Resend verification email error: {e}");
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(ResendVerificationEmailStatus::InternalServerError))
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use axum::{
+    extract::{Path, State, Json},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+
+use crate::credential::Credential;
+use crate::data::{Database, GetGradeOutcome};
+use crate::types::Grade;
+
+#[derive(Serialize)]
+pub enum GetGradeStatus {
+    Success(Grade),
+    InternalServerError,
+    NotLoggedIn,
+    NotFound,
+}
+
+pub async fn get(
+    State(db): State<Database>,
+    Credential(credential): Credential,
+    Path(submission_id): Path<i32>,
+) -> impl IntoResponse {
+    if let Some(credential) = credential {
+        match db.get_grade(credential, submission_id).await {
+            Ok(GetGradeOutcome::Success(grade)) => (StatusCode::OK, Json(GetGradeStatus::Success(grade))),
+            Ok(GetGradeOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(GetGradeStatus::NotLoggedIn)),
+            Ok(GetGradeOutcome::NotFound) => (StatusCode::NOT_FOUND, Json(GetGradeStatus::NotFound)),
+            Err(e) => {
+                eprintln!("Get grade error: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(GetGradeStatus::InternalServerError))
+            }
+        }
+    } else {
+        (StatusCode::UNAUTHORIZED, Json(GetGradeStatus::NotLoggedIn))
+    }
+}
@@ -0,0 +1,45 @@
+use axum::{
+    extract::{Path, State, Json},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+
+use crate::credential::Credential;
+use crate::data::{Database, GradeSubmissionOutcome};
+use crate::types::NewGrade;
+
+#[derive(Serialize)]
+pub enum GradeSubmissionStatus {
+    Success,
+    InternalServerError,
+    NotLoggedIn,
+    NotPermitted,
+    SubmissionNotFound,
+    InvalidPoints,
+    RoomArchived,
+}
+
+pub async fn grade(
+    State(db): State<Database>,
+    Credential(credential): Credential,
+    Path(submission_id): Path<i32>,
+    Json(grade): Json<NewGrade>,
+) -> impl IntoResponse {
+    if let Some(credential) = credential {
+        match db.grade_submission(credential, submission_id, grade).await {
+            Ok(GradeSubmissionOutcome::Success) => (StatusCode::OK, Json(GradeSubmissionStatus::Success)),
+            Ok(GradeSubmissionOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(GradeSubmissionStatus::NotLoggedIn)),
+            Ok(GradeSubmissionOutcome::NotPermitted) => (StatusCode::UNAUTHORIZED, Json(GradeSubmissionStatus::NotPermitted)),
+            Ok(GradeSubmissionOutcome::SubmissionNotFound) => (StatusCode::NOT_FOUND, Json(GradeSubmissionStatus::SubmissionNotFound)),
+            Ok(GradeSubmissionOutcome::InvalidPoints) => (StatusCode::BAD_REQUEST, Json(GradeSubmissionStatus::InvalidPoints)),
+            Ok(GradeSubmissionOutcome::RoomArchived) => (StatusCode::CONFLICT, Json(GradeSubmissionStatus::RoomArchived)),
+            Err(e) => {
+                eprintln!("Grade submission error: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(GradeSubmissionStatus::InternalServerError))
+            }
+        }
+    } else {
+        (StatusCode::UNAUTHORIZED, Json(GradeSubmissionStatus::NotLoggedIn))
+    }
+}
@@ -0,0 +1,154 @@
+use axum::{
+    body::Body,
+    extract::{Path, Query, State, Json},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use futures_util::{stream, StreamExt};
+use serde::{Deserialize, Serialize};
+
+use crate::credential::Credential;
+use crate::data::{Database, ExportGradebookOutcome};
+use crate::types::{Gradebook, GradebookRow};
+
+#[derive(Serialize)]
+pub enum ExportGradebookStatus {
+    InternalServerError,
+    NotLoggedIn,
+    NotPermitted,
+}
+
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum GradebookFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+#[derive(Deserialize)]
+pub struct GradebookQuery {
+    #[serde(default)]
+    pub format: GradebookFormat,
+}
+
+pub async fn gradebook(
+    State(db): State<Database>,
+    Credential(credential): Credential,
+    Path(room_id): Path<i32>,
+    Query(query): Query<GradebookQuery>,
+) -> Response {
+    let Some(credential) = credential else {
+        return (StatusCode::UNAUTHORIZED, Json(ExportGradebookStatus::NotLoggedIn)).into_response();
+    };
+
+    match db.export_gradebook(credential, room_id).await {
+        Ok(ExportGradebookOutcome::Success(gradebook)) => {
+            let (content_type, extension, body) = match query.format {
+                GradebookFormat::Csv => ("text/csv; charset=utf-8", "csv", csv_body(gradebook)),
+                GradebookFormat::Json => ("application/json", "json", json_body(gradebook)),
+            };
+
+            (
+                StatusCode::OK,
+                [
+                    (header::CONTENT_TYPE, content_type.to_string()),
+                    (header::CONTENT_DISPOSITION, format!("attachment; filename=\"gradebook-{room_id}.{extension}\"")),
+                ],
+                body,
+            )
+                .into_response()
+        }
+        Ok(ExportGradebookOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(ExportGradebookStatus::NotLoggedIn)).into_response(),
+        Ok(ExportGradebookOutcome::NotPermitted) => (StatusCode::UNAUTHORIZED, Json(ExportGradebookStatus::NotPermitted)).into_response(),
+        Err(e) => {
+            eprintln!("Export gradebook error: {e}");
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(ExportGradebookStatus::InternalServerError)).into_response()
+        }
+    }
+}
+
+/// Turns the channel of gradebook rows into a stream, ending when the sender is dropped.
+fn row_stream(gradebook: Gradebook) -> impl futures_util::Stream<Item = anyhow::Result<GradebookRow>> {
+    stream::unfold(gradebook.rows, |mut rows| async move {
+        rows.recv().await.map(|row| (row, rows))
+    })
+}
+
+fn csv_body(gradebook: Gradebook) -> Body {
+    let mut header = vec![
+        "user_id".to_string(),
+        "name".to_string(),
+        "surname".to_string(),
+        "email".to_string(),
+    ];
+    for assignment in &gradebook.assignments {
+        header.push(assignment.title.clone());
+        header.push(format!("{} (late)", assignment.title));
+    }
+    header.push("total".to_string());
+
+    let header = csv_line(&header);
+
+    let rows = row_stream(gradebook).map(|row| {
+        row.map(|row| {
+            let mut fields = vec![
+                row.user_id.to_string(),
+                row.name,
+                row.surname,
+                row.email,
+            ];
+            for cell in row.grades {
+                fields.push(cell.points.map(|p| p.to_string()).unwrap_or_default());
+                fields.push(if cell.late { "yes" } else { "" }.to_string());
+            }
+            fields.push(row.total.to_string());
+
+            csv_line(&fields)
+        })
+    });
+
+    Body::from_stream(stream::once(async { Ok(header) }).chain(rows))
+}
+
+fn json_body(gradebook: Gradebook) -> Body {
+    let assignments = match serde_json::to_string(&gradebook.assignments) {
+        Ok(assignments) => assignments,
+        Err(e) => return Body::from_stream(stream::once(async { Err::<String, _>(anyhow::Error::from(e)) })),
+    };
+
+    let head = format!("{{\"assignments\":{assignments},\"members\":[");
+
+    let rows = row_stream(gradebook).enumerate().map(|(i, row)| -> anyhow::Result<String> {
+        let row = serde_json::to_string(&row?)?;
+        Ok(if i == 0 { row } else { format!(",{row}") })
+    });
+
+    Body::from_stream(
+        stream::once(async { Ok(head) })
+            .chain(rows)
+            .chain(stream::once(async { Ok("]}".to_string()) })),
+    )
+}
+
+/// Formats a single CSV record, including the trailing line break.
+fn csv_line(fields: &[String]) -> String {
+    let mut line = fields.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(",");
+    line.push_str("\r\n");
+    line
+}
+
+fn csv_field(field: &str) -> String {
+    // Stop spreadsheet applications from evaluating user supplied values as formulas
+    let field = if field.starts_with(['=', '+', '-', '@']) && field.parse::<f64>().is_err() {
+        format!("'{field}")
+    } else {
+        field.to_string()
+    };
+
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
@@ -0,0 +1,35 @@
+use axum::{
+    extract::{State, Json},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+
+use crate::credential::Credential;
+use crate::data::{Database, GetMyGradesOutcome};
+use crate::types::Grade;
+
+#[derive(Serialize)]
+pub enum GetMyGradesStatus {
+    Success(Vec<Grade>),
+    InternalServerError,
+    NotLoggedIn,
+}
+
+pub async fn mine(
+    State(db): State<Database>,
+    Credential(credential): Credential,
+) -> impl IntoResponse {
+    if let Some(credential) = credential {
+        match db.get_my_grades(credential).await {
+            Ok(GetMyGradesOutcome::Success(grades)) => (StatusCode::OK, Json(GetMyGradesStatus::Success(grades))),
+            Ok(GetMyGradesOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(GetMyGradesStatus::NotLoggedIn)),
+            Err(e) => {
+                eprintln!("Get my grades error: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(GetMyGradesStatus::InternalServerError))
+            }
+        }
+    } else {
+        (StatusCode::UNAUTHORIZED, Json(GetMyGradesStatus::NotLoggedIn))
+    }
+}
@@ -0,0 +1,11 @@
+mod get;
+pub use get::get;
+
+mod grade;
+pub use grade::grade;
+
+mod gradebook;
+pub use gradebook::gradebook;
+
+mod mine;
+pub use mine::mine;
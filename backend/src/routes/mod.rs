@@ -1,3 +1,7 @@
+pub mod assignments;
 pub mod auth;
+pub mod grades;
 pub mod rooms;
+pub mod submissions;
+pub mod users;
 pub mod health;
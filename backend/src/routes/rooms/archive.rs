@@ -0,0 +1,55 @@
+use axum::{
+    extract::{Path, State, Json},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+
+use crate::credential::Credential;
+use crate::data::{Database, SetArchivedOutcome};
+
+#[derive(Serialize)]
+pub enum SetArchivedStatus {
+    Success,
+    InternalServerError,
+    NotLoggedIn,
+    NotOwner,
+}
+
+/// Makes a room read-only, e.g. at the end of a term.
+pub async fn archive(
+    State(db): State<Database>,
+    Credential(credential): Credential,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    set_archived(db, credential, id, true).await
+}
+
+pub async fn unarchive(
+    State(db): State<Database>,
+    Credential(credential): Credential,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    set_archived(db, credential, id, false).await
+}
+
+async fn set_archived(
+    db: Database,
+    credential: Option<String>,
+    id: i32,
+    archived: bool,
+) -> (StatusCode, Json<SetArchivedStatus>) {
+    let Some(credential) = credential else {
+        return (StatusCode::UNAUTHORIZED, Json(SetArchivedStatus::NotLoggedIn));
+    };
+
+    match db.set_room_archived(credential, id, archived).await {
+        Ok(SetArchivedOutcome::Success) => (StatusCode::OK, Json(SetArchivedStatus::Success)),
+        Ok(SetArchivedOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(SetArchivedStatus::NotLoggedIn)),
+        Ok(SetArchivedOutcome::NotOwner) => (StatusCode::UNAUTHORIZED, Json(SetArchivedStatus::NotOwner)),
+        Err(e) => {
+            eprintln!("Set room archived error: {e}");
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(SetArchivedStatus::InternalServerError))
+        }
+    }
+}
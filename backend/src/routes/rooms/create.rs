@@ -3,9 +3,9 @@ use axum::{
     http::StatusCode,
     response::IntoResponse,
 };
-use tower_cookies::Cookies;
 use serde::Serialize;
 
+use crate::credential::Credential;
 use crate::data::{Database, CreateRoomOutcome};
 use crate::types::NewRoom;
 
@@ -18,11 +18,11 @@ pub enum CreateRoomStatus {
 
 pub async fn create(
     State(db): State<Database>,
-    cookies: Cookies,
+    Credential(credential): Credential,
     Json(room): Json<NewRoom>,
 ) -> impl IntoResponse {
-    if let Some(session_uuid_cookie) = cookies.get("session_uuid") {
-        match db.create_room(session_uuid_cookie.value().to_string(), room).await {
+    if let Some(credential) = credential {
+        match db.create_room(credential, room).await {
             Ok(CreateRoomOutcome::Success) => (StatusCode::OK, Json(CreateRoomStatus::Success)),
             Ok(CreateRoomOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(CreateRoomStatus::InvalidCredentials)),
             Err(e) => {
@@ -0,0 +1,63 @@
+use axum::{
+    extract::{Path, State, Json},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+
+use crate::credential::Credential;
+use crate::data::{Database, DecideJoinRequestOutcome};
+
+#[derive(Serialize)]
+pub enum DecideJoinRequestStatus {
+    Success,
+    InternalServerError,
+    NotLoggedIn,
+    NotPermitted,
+    RequestNotFound,
+    RoomArchived,
+    Banned,
+    CodeExhausted,
+}
+
+pub async fn approve_join_request(
+    State(db): State<Database>,
+    Credential(credential): Credential,
+    Path((id, user_id)): Path<(i32, i32)>,
+) -> impl IntoResponse {
+    decide(db, credential, id, user_id, true).await
+}
+
+pub async fn reject_join_request(
+    State(db): State<Database>,
+    Credential(credential): Credential,
+    Path((id, user_id)): Path<(i32, i32)>,
+) -> impl IntoResponse {
+    decide(db, credential, id, user_id, false).await
+}
+
+async fn decide(
+    db: Database,
+    credential: Option<String>,
+    id: i32,
+    user_id: i32,
+    approve: bool,
+) -> (StatusCode, Json<DecideJoinRequestStatus>) {
+    if let Some(credential) = credential {
+        match db.decide_join_request(credential, id, user_id, approve).await {
+            Ok(DecideJoinRequestOutcome::Success) => (StatusCode::OK, Json(DecideJoinRequestStatus::Success)),
+            Ok(DecideJoinRequestOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(DecideJoinRequestStatus::NotLoggedIn)),
+            Ok(DecideJoinRequestOutcome::NotPermitted) => (StatusCode::UNAUTHORIZED, Json(DecideJoinRequestStatus::NotPermitted)),
+            Ok(DecideJoinRequestOutcome::RequestNotFound) => (StatusCode::NOT_FOUND, Json(DecideJoinRequestStatus::RequestNotFound)),
+            Ok(DecideJoinRequestOutcome::RoomArchived) => (StatusCode::CONFLICT, Json(DecideJoinRequestStatus::RoomArchived)),
+            Ok(DecideJoinRequestOutcome::Banned) => (StatusCode::CONFLICT, Json(DecideJoinRequestStatus::Banned)),
+            Ok(DecideJoinRequestOutcome::CodeExhausted) => (StatusCode::CONFLICT, Json(DecideJoinRequestStatus::CodeExhausted)),
+            Err(e) => {
+                eprintln!("Decide join request error: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(DecideJoinRequestStatus::InternalServerError))
+            }
+        }
+    } else {
+        (StatusCode::UNAUTHORIZED, Json(DecideJoinRequestStatus::NotLoggedIn))
+    }
+}
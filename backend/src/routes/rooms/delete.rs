@@ -3,9 +3,9 @@ use axum::{
     http::StatusCode,
     response::IntoResponse,
 };
-use tower_cookies::Cookies;
 use serde::Serialize;
 
+use crate::credential::Credential;
 use crate::data::{Database, DeleteRoomOutcome};
 
 #[derive(Serialize)]
@@ -18,11 +18,11 @@ pub enum DeleteRoomStatus {
 
 pub async fn delete(
     State(db): State<Database>,
-    cookies: Cookies,
+    Credential(credential): Credential,
     Path(id): Path<i32>,
 ) -> impl IntoResponse {
-    if let Some(session_uuid_cookie) = cookies.get("session_uuid") {
-        match db.delete_room(session_uuid_cookie.value().to_string(), id).await {
+    if let Some(credential) = credential {
+        match db.delete_room(credential, id).await {
             Ok(DeleteRoomOutcome::Success) => (StatusCode::OK, Json(DeleteRoomStatus::Success)),
             Ok(DeleteRoomOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(DeleteRoomStatus::NotLoggedIn)),
             Ok(DeleteRoomOutcome::NotOwner) => (StatusCode::UNAUTHORIZED, Json(DeleteRoomStatus::NotOwner)),
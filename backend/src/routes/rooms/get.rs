@@ -1,11 +1,11 @@
 use axum::{
-    extract::{State, Json},
+    extract::{Query, State, Json},
     http::StatusCode,
     response::IntoResponse,
 };
-use tower_cookies::Cookies;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
+use crate::credential::Credential;
 use crate::data::{Database, GetRoomOutcome};
 use crate::types::Room;
 
@@ -16,13 +16,20 @@ pub enum GetRoomStatus {
     InternalServerError,
 }
 
+#[derive(Deserialize)]
+pub struct GetRoomsQuery {
+    /// Only archived rooms if `true`, only active ones if `false`, all if left out
+    pub archived: Option<bool>,
+}
+
 pub async fn get(
     State(db): State<Database>,
-    cookies: Cookies,
+    Credential(credential): Credential,
+    Query(query): Query<GetRoomsQuery>,
 ) -> impl IntoResponse {
-    if let Some(session_uuid_cookie) = cookies.get("session_uuid") {
+    if let Some(credential) = credential {
 
-        match db.get_rooms(session_uuid_cookie.value().to_string()).await {
+        match db.get_rooms(credential, query.archived).await {
             Ok(GetRoomOutcome::Success(rooms)) => (StatusCode::OK, Json(GetRoomStatus::Success(rooms))),
             Ok(GetRoomOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(GetRoomStatus::InvalidCredentials)),
             Err(e) => {
@@ -3,29 +3,34 @@ use axum::{
     http::StatusCode,
     response::IntoResponse,
 };
-use tower_cookies::Cookies;
 use serde::Serialize;
 
+use crate::credential::Credential;
 use crate::data::{Database, InvitationCodeOutcome};
+use crate::types::InvitationCode;
 
 #[derive(Serialize)]
 pub enum InvitationCodeStatus {
-    Success(String),
+    Success(InvitationCode),
     InternalServerError,
     NotLoggedIn,
-    NotOwner,
+    NotPermitted,
+    InsufficientScope,
+    RoomArchived,
 }
 
 pub async fn invitation_code(
     State(db): State<Database>,
-    cookies: Cookies,
+    Credential(credential): Credential,
     Path(id): Path<i32>,
 ) -> impl IntoResponse {
-    if let Some(session_uuid_cookie) = cookies.get("session_uuid") {
-        match db.get_invitation_code(session_uuid_cookie.value().to_string(), id).await {
+    if let Some(credential) = credential {
+        match db.get_invitation_code(credential, id).await {
             Ok(InvitationCodeOutcome::Success(code)) => (StatusCode::OK, Json(InvitationCodeStatus::Success(code))),
             Ok(InvitationCodeOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(InvitationCodeStatus::NotLoggedIn)),
-            Ok(InvitationCodeOutcome::NotOwner) => (StatusCode::UNAUTHORIZED, Json(InvitationCodeStatus::NotOwner)),
+            Ok(InvitationCodeOutcome::NotPermitted) => (StatusCode::UNAUTHORIZED, Json(InvitationCodeStatus::NotPermitted)),
+            Ok(InvitationCodeOutcome::InsufficientScope) => (StatusCode::FORBIDDEN, Json(InvitationCodeStatus::InsufficientScope)),
+            Ok(InvitationCodeOutcome::RoomArchived) => (StatusCode::CONFLICT, Json(InvitationCodeStatus::RoomArchived)),
             Err(e) => {
                 eprintln!("Invitation code error: {e}");
                 (StatusCode::INTERNAL_SERVER_ERROR, Json(InvitationCodeStatus::InternalServerError))
@@ -3,32 +3,53 @@ use axum::{
     http::StatusCode,
     response::IntoResponse,
 };
-use tower_cookies::Cookies;
 use serde::Serialize;
+use std::sync::Arc;
 
+use crate::credential::Credential;
 use crate::data::{Database, JoinRoomOutcome};
+use crate::verification::EmailVerification;
 
 #[derive(Serialize)]
 pub enum JoinRoomStatus {
     Success,
+    RequestPending,
+    RequestAlreadyPending,
     AlreadyMember,
     InvalidCode,
+    CodeRevoked,
+    CodeExpired,
+    CodeExhausted,
     InvalidCredentials,
     InternalServerError,
+    Banned,
+    EmailNotVerified,
+    RoomArchived,
 }
 
 pub async fn join(
     State(db): State<Database>,
-    cookies: Cookies,
+    State(verification): State<Arc<EmailVerification>>,
+    Credential(credential): Credential,
     Path(code): Path<String>,
 ) -> impl IntoResponse {
-    if let Some(session_uuid_cookie) = cookies.get("session_uuid") {
+    if let Some(credential) = credential {
 
-        match db.join_room(session_uuid_cookie.value().to_string(), code).await {
+        let require_verified_email = !verification.may_join_unverified();
+
+        match db.join_room(credential, code, require_verified_email).await {
             Ok(JoinRoomOutcome::Success) => (StatusCode::OK, Json(JoinRoomStatus::Success)),
+            Ok(JoinRoomOutcome::RequestPending) => (StatusCode::ACCEPTED, Json(JoinRoomStatus::RequestPending)),
+            Ok(JoinRoomOutcome::RequestAlreadyPending) => (StatusCode::CONFLICT, Json(JoinRoomStatus::RequestAlreadyPending)),
             Ok(JoinRoomOutcome::AlreadyMember) => (StatusCode::CONFLICT, Json(JoinRoomStatus::AlreadyMember)),
             Ok(JoinRoomOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(JoinRoomStatus::InvalidCredentials)),
             Ok(JoinRoomOutcome::InvalidCode) => (StatusCode::NOT_FOUND, Json(JoinRoomStatus::InvalidCode)),
+            Ok(JoinRoomOutcome::CodeRevoked) => (StatusCode::GONE, Json(JoinRoomStatus::CodeRevoked)),
+            Ok(JoinRoomOutcome::CodeExpired) => (StatusCode::GONE, Json(JoinRoomStatus::CodeExpired)),
+            Ok(JoinRoomOutcome::CodeExhausted) => (StatusCode::GONE, Json(JoinRoomStatus::CodeExhausted)),
+            Ok(JoinRoomOutcome::Banned) => (StatusCode::FORBIDDEN, Json(JoinRoomStatus::Banned)),
+            Ok(JoinRoomOutcome::EmailNotVerified) => (StatusCode::FORBIDDEN, Json(JoinRoomStatus::EmailNotVerified)),
+            Ok(JoinRoomOutcome::RoomArchived) => (StatusCode::CONFLICT, Json(JoinRoomStatus::RoomArchived)),
             Err(e) => {
                 eprintln!("Join room error: {e}");
                 (StatusCode::INTERNAL_SERVER_ERROR, Json(JoinRoomStatus::InternalServerError))
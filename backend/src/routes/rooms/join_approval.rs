@@ -0,0 +1,39 @@
+use axum::{
+    extract::{Path, State, Json},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+
+use crate::credential::Credential;
+use crate::data::{Database, SetJoinApprovalOutcome};
+use crate::types::JoinApproval;
+
+#[derive(Serialize)]
+pub enum SetJoinApprovalStatus {
+    Success,
+    InternalServerError,
+    NotLoggedIn,
+    NotOwner,
+}
+
+pub async fn join_approval(
+    State(db): State<Database>,
+    Credential(credential): Credential,
+    Path(id): Path<i32>,
+    Json(approval): Json<JoinApproval>,
+) -> impl IntoResponse {
+    if let Some(credential) = credential {
+        match db.set_join_approval(credential, id, approval).await {
+            Ok(SetJoinApprovalOutcome::Success) => (StatusCode::OK, Json(SetJoinApprovalStatus::Success)),
+            Ok(SetJoinApprovalOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(SetJoinApprovalStatus::NotLoggedIn)),
+            Ok(SetJoinApprovalOutcome::NotOwner) => (StatusCode::UNAUTHORIZED, Json(SetJoinApprovalStatus::NotOwner)),
+            Err(e) => {
+                eprintln!("Set join approval error: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(SetJoinApprovalStatus::InternalServerError))
+            }
+        }
+    } else {
+        (StatusCode::UNAUTHORIZED, Json(SetJoinApprovalStatus::NotLoggedIn))
+    }
+}
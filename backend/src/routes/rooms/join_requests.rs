@@ -0,0 +1,38 @@
+use axum::{
+    extract::{Path, State, Json},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+
+use crate::credential::Credential;
+use crate::data::{Database, ListJoinRequestsOutcome};
+use crate::types::JoinRequest;
+
+#[derive(Serialize)]
+pub enum ListJoinRequestsStatus {
+    Success(Vec<JoinRequest>),
+    InternalServerError,
+    NotLoggedIn,
+    NotPermitted,
+}
+
+pub async fn join_requests(
+    State(db): State<Database>,
+    Credential(credential): Credential,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    if let Some(credential) = credential {
+        match db.list_join_requests(credential, id).await {
+            Ok(ListJoinRequestsOutcome::Success(requests)) => (StatusCode::OK, Json(ListJoinRequestsStatus::Success(requests))),
+            Ok(ListJoinRequestsOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(ListJoinRequestsStatus::NotLoggedIn)),
+            Ok(ListJoinRequestsOutcome::NotPermitted) => (StatusCode::UNAUTHORIZED, Json(ListJoinRequestsStatus::NotPermitted)),
+            Err(e) => {
+                eprintln!("List join requests error: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(ListJoinRequestsStatus::InternalServerError))
+            }
+        }
+    } else {
+        (StatusCode::UNAUTHORIZED, Json(ListJoinRequestsStatus::NotLoggedIn))
+    }
+}
@@ -3,9 +3,9 @@ use axum::{
     http::StatusCode,
     response::IntoResponse,
 };
-use tower_cookies::Cookies;
 use serde::Serialize;
 
+use crate::credential::Credential;
 use crate::data::{Database, LeaveRoomOutcome};
 
 #[derive(Serialize)]
@@ -19,11 +19,11 @@ pub enum LeaveRoomStatus {
 
 pub async fn leave(
     State(db): State<Database>,
-    cookies: Cookies,
+    Credential(credential): Credential,
     Path(id): Path<i32>,
 ) -> impl IntoResponse {
-    if let Some(session_uuid_cookie) = cookies.get("session_uuid") {
-        match db.leave_room(session_uuid_cookie.value().to_string(), id).await {
+    if let Some(credential) = credential {
+        match db.leave_room(credential, id).await {
             Ok(LeaveRoomOutcome::Success) => (StatusCode::OK, Json(LeaveRoomStatus::Success)),
             Ok(LeaveRoomOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(LeaveRoomStatus::NotLoggedIn)),
             Ok(LeaveRoomOutcome::NotMember) => (StatusCode::BAD_REQUEST, Json(LeaveRoomStatus::NotMember)),
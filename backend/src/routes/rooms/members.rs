@@ -0,0 +1,38 @@
+use axum::{
+    extract::{Path, State, Json},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+
+use crate::credential::Credential;
+use crate::data::{Database, ListMembersOutcome};
+use crate::types::RoomMember;
+
+#[derive(Serialize)]
+pub enum ListMembersStatus {
+    Success(Vec<RoomMember>),
+    InternalServerError,
+    NotLoggedIn,
+    NotPermitted,
+}
+
+pub async fn members(
+    State(db): State<Database>,
+    Credential(credential): Credential,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    if let Some(credential) = credential {
+        match db.list_members(credential, id).await {
+            Ok(ListMembersOutcome::Success(members)) => (StatusCode::OK, Json(ListMembersStatus::Success(members))),
+            Ok(ListMembersOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(ListMembersStatus::NotLoggedIn)),
+            Ok(ListMembersOutcome::NotPermitted) => (StatusCode::UNAUTHORIZED, Json(ListMembersStatus::NotPermitted)),
+            Err(e) => {
+                eprintln!("List members error: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(ListMembersStatus::InternalServerError))
+            }
+        }
+    } else {
+        (StatusCode::UNAUTHORIZED, Json(ListMembersStatus::NotLoggedIn))
+    }
+}
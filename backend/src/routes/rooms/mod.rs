@@ -1,6 +1,12 @@
+mod archive;
+pub use archive::{archive, unarchive};
+
 mod create;
 pub use create::create;
 
+mod decide_join_request;
+pub use decide_join_request::{approve_join_request, reject_join_request};
+
 mod delete;
 pub use delete::delete;
 
@@ -13,5 +19,38 @@ pub use invitation_code::invitation_code;
 mod join;
 pub use join::join;
 
+mod join_approval;
+pub use join_approval::join_approval;
+
+mod join_requests;
+pub use join_requests::join_requests;
+
 mod leave;
 pub use leave::leave;
+
+mod members;
+pub use members::members;
+
+mod my_join_requests;
+pub use my_join_requests::my_join_requests;
+
+mod remove_member;
+pub use remove_member::remove_member;
+
+mod revoke_invitation_code;
+pub use revoke_invitation_code::revoke_invitation_code;
+
+mod role;
+pub use role::set_role;
+
+mod rotate_invitation_code;
+pub use rotate_invitation_code::rotate_invitation_code;
+
+mod transfer;
+pub use transfer::transfer;
+
+mod trash;
+pub use trash::{restore, trash};
+
+mod update;
+pub use update::update;
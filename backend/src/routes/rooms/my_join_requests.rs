@@ -0,0 +1,35 @@
+use axum::{
+    extract::{State, Json},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+
+use crate::credential::Credential;
+use crate::data::{Database, GetMyJoinRequestsOutcome};
+use crate::types::JoinRequest;
+
+#[derive(Serialize)]
+pub enum GetMyJoinRequestsStatus {
+    Success(Vec<JoinRequest>),
+    InternalServerError,
+    NotLoggedIn,
+}
+
+pub async fn my_join_requests(
+    State(db): State<Database>,
+    Credential(credential): Credential,
+) -> impl IntoResponse {
+    if let Some(credential) = credential {
+        match db.get_my_join_requests(credential).await {
+            Ok(GetMyJoinRequestsOutcome::Success(requests)) => (StatusCode::OK, Json(GetMyJoinRequestsStatus::Success(requests))),
+            Ok(GetMyJoinRequestsOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(GetMyJoinRequestsStatus::NotLoggedIn)),
+            Err(e) => {
+                eprintln!("Get my join requests error: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(GetMyJoinRequestsStatus::InternalServerError))
+            }
+        }
+    } else {
+        (StatusCode::UNAUTHORIZED, Json(GetMyJoinRequestsStatus::NotLoggedIn))
+    }
+}
@@ -0,0 +1,50 @@
+use axum::{
+    extract::{Path, Query, State, Json},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::credential::Credential;
+use crate::data::{Database, RemoveMemberOutcome};
+
+#[derive(Serialize)]
+pub enum RemoveMemberStatus {
+    Success,
+    InternalServerError,
+    NotLoggedIn,
+    NotPermitted,
+    NotMember,
+    CannotRemoveOwner,
+    RoomArchived,
+}
+
+#[derive(Deserialize)]
+pub struct RemoveMemberQuery {
+    #[serde(default)]
+    pub ban: bool,
+}
+
+pub async fn remove_member(
+    State(db): State<Database>,
+    Credential(credential): Credential,
+    Path((id, user_id)): Path<(i32, i32)>,
+    Query(query): Query<RemoveMemberQuery>,
+) -> impl IntoResponse {
+    if let Some(credential) = credential {
+        match db.remove_member(credential, id, user_id, query.ban).await {
+            Ok(RemoveMemberOutcome::Success) => (StatusCode::OK, Json(RemoveMemberStatus::Success)),
+            Ok(RemoveMemberOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(RemoveMemberStatus::NotLoggedIn)),
+            Ok(RemoveMemberOutcome::NotPermitted) => (StatusCode::UNAUTHORIZED, Json(RemoveMemberStatus::NotPermitted)),
+            Ok(RemoveMemberOutcome::NotMember) => (StatusCode::NOT_FOUND, Json(RemoveMemberStatus::NotMember)),
+            Ok(RemoveMemberOutcome::CannotRemoveOwner) => (StatusCode::BAD_REQUEST, Json(RemoveMemberStatus::CannotRemoveOwner)),
+            Ok(RemoveMemberOutcome::RoomArchived) => (StatusCode::CONFLICT, Json(RemoveMemberStatus::RoomArchived)),
+            Err(e) => {
+                eprintln!("Remove member error: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(RemoveMemberStatus::InternalServerError))
+            }
+        }
+    } else {
+        (StatusCode::UNAUTHORIZED, Json(RemoveMemberStatus::NotLoggedIn))
+    }
+}
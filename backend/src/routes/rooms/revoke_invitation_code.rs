@@ -0,0 +1,39 @@
+use axum::{
+    extract::{Path, State, Json},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+
+use crate::credential::Credential;
+use crate::data::{Database, RevokeInvitationCodeOutcome};
+
+#[derive(Serialize)]
+pub enum RevokeInvitationCodeStatus {
+    Success,
+    InternalServerError,
+    NotLoggedIn,
+    NotPermitted,
+    RoomArchived,
+}
+
+pub async fn revoke_invitation_code(
+    State(db): State<Database>,
+    Credential(credential): Credential,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    if let Some(credential) = credential {
+        match db.revoke_invitation_code(credential, id).await {
+            Ok(RevokeInvitationCodeOutcome::Success) => (StatusCode::OK, Json(RevokeInvitationCodeStatus::Success)),
+            Ok(RevokeInvitationCodeOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(RevokeInvitationCodeStatus::NotLoggedIn)),
+            Ok(RevokeInvitationCodeOutcome::NotPermitted) => (StatusCode::UNAUTHORIZED, Json(RevokeInvitationCodeStatus::NotPermitted)),
+            Ok(RevokeInvitationCodeOutcome::RoomArchived) => (StatusCode::CONFLICT, Json(RevokeInvitationCodeStatus::RoomArchived)),
+            Err(e) => {
+                eprintln!("Revoke invitation code error: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(RevokeInvitationCodeStatus::InternalServerError))
+            }
+        }
+    } else {
+        (StatusCode::UNAUTHORIZED, Json(RevokeInvitationCodeStatus::NotLoggedIn))
+    }
+}
@@ -0,0 +1,45 @@
+use axum::{
+    extract::{Path, State, Json},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+
+use crate::credential::Credential;
+use crate::data::{Database, SetMemberRoleOutcome};
+use crate::types::NewMemberRole;
+
+#[derive(Serialize)]
+pub enum SetMemberRoleStatus {
+    Success,
+    InternalServerError,
+    NotLoggedIn,
+    NotOwner,
+    NotMember,
+    CannotChangeOwner,
+    RoomArchived,
+}
+
+pub async fn set_role(
+    State(db): State<Database>,
+    Credential(credential): Credential,
+    Path((id, user_id)): Path<(i32, i32)>,
+    Json(role): Json<NewMemberRole>,
+) -> impl IntoResponse {
+    if let Some(credential) = credential {
+        match db.set_member_role(credential, id, user_id, role).await {
+            Ok(SetMemberRoleOutcome::Success) => (StatusCode::OK, Json(SetMemberRoleStatus::Success)),
+            Ok(SetMemberRoleOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(SetMemberRoleStatus::NotLoggedIn)),
+            Ok(SetMemberRoleOutcome::NotOwner) => (StatusCode::UNAUTHORIZED, Json(SetMemberRoleStatus::NotOwner)),
+            Ok(SetMemberRoleOutcome::NotMember) => (StatusCode::NOT_FOUND, Json(SetMemberRoleStatus::NotMember)),
+            Ok(SetMemberRoleOutcome::CannotChangeOwner) => (StatusCode::BAD_REQUEST, Json(SetMemberRoleStatus::CannotChangeOwner)),
+            Ok(SetMemberRoleOutcome::RoomArchived) => (StatusCode::CONFLICT, Json(SetMemberRoleStatus::RoomArchived)),
+            Err(e) => {
+                eprintln!("Set member role error: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(SetMemberRoleStatus::InternalServerError))
+            }
+        }
+    } else {
+        (StatusCode::UNAUTHORIZED, Json(SetMemberRoleStatus::NotLoggedIn))
+    }
+}
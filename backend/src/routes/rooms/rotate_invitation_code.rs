@@ -0,0 +1,43 @@
+use axum::{
+    extract::{Path, State, Json},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+
+use crate::credential::Credential;
+use crate::data::{Database, RotateInvitationCodeOutcome};
+use crate::types::{InvitationCode, NewInvitationCode};
+
+#[derive(Serialize)]
+pub enum RotateInvitationCodeStatus {
+    Success(InvitationCode),
+    InternalServerError,
+    NotLoggedIn,
+    NotPermitted,
+    InvalidSettings,
+    RoomArchived,
+}
+
+pub async fn rotate_invitation_code(
+    State(db): State<Database>,
+    Credential(credential): Credential,
+    Path(id): Path<i32>,
+    Json(settings): Json<NewInvitationCode>,
+) -> impl IntoResponse {
+    if let Some(credential) = credential {
+        match db.rotate_invitation_code(credential, id, settings).await {
+            Ok(RotateInvitationCodeOutcome::Success(code)) => (StatusCode::OK, Json(RotateInvitationCodeStatus::Success(code))),
+            Ok(RotateInvitationCodeOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(RotateInvitationCodeStatus::NotLoggedIn)),
+            Ok(RotateInvitationCodeOutcome::NotPermitted) => (StatusCode::UNAUTHORIZED, Json(RotateInvitationCodeStatus::NotPermitted)),
+            Ok(RotateInvitationCodeOutcome::InvalidSettings) => (StatusCode::BAD_REQUEST, Json(RotateInvitationCodeStatus::InvalidSettings)),
+            Ok(RotateInvitationCodeOutcome::RoomArchived) => (StatusCode::CONFLICT, Json(RotateInvitationCodeStatus::RoomArchived)),
+            Err(e) => {
+                eprintln!("Rotate invitation code error: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(RotateInvitationCodeStatus::InternalServerError))
+            }
+        }
+    } else {
+        (StatusCode::UNAUTHORIZED, Json(RotateInvitationCodeStatus::NotLoggedIn))
+    }
+}
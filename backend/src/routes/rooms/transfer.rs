@@ -0,0 +1,43 @@
+use axum::{
+    extract::{Path, State, Json},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+
+use crate::credential::Credential;
+use crate::data::{Database, TransferOwnershipOutcome};
+use crate::types::TransferOwnership;
+
+#[derive(Serialize)]
+pub enum TransferOwnershipStatus {
+    Success,
+    InternalServerError,
+    NotLoggedIn,
+    NotOwner,
+    NotMember,
+    AlreadyOwner,
+}
+
+pub async fn transfer(
+    State(db): State<Database>,
+    Credential(credential): Credential,
+    Path(id): Path<i32>,
+    Json(transfer): Json<TransferOwnership>,
+) -> impl IntoResponse {
+    if let Some(credential) = credential {
+        match db.transfer_ownership(credential, id, transfer).await {
+            Ok(TransferOwnershipOutcome::Success) => (StatusCode::OK, Json(TransferOwnershipStatus::Success)),
+            Ok(TransferOwnershipOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(TransferOwnershipStatus::NotLoggedIn)),
+            Ok(TransferOwnershipOutcome::NotOwner) => (StatusCode::UNAUTHORIZED, Json(TransferOwnershipStatus::NotOwner)),
+            Ok(TransferOwnershipOutcome::NotMember) => (StatusCode::NOT_FOUND, Json(TransferOwnershipStatus::NotMember)),
+            Ok(TransferOwnershipOutcome::AlreadyOwner) => (StatusCode::BAD_REQUEST, Json(TransferOwnershipStatus::AlreadyOwner)),
+            Err(e) => {
+                eprintln!("Transfer ownership error: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(TransferOwnershipStatus::InternalServerError))
+            }
+        }
+    } else {
+        (StatusCode::UNAUTHORIZED, Json(TransferOwnershipStatus::NotLoggedIn))
+    }
+}
@@ -0,0 +1,69 @@
+use axum::{
+    extract::{Path, State, Json},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::credential::Credential;
+use crate::data::{Database, ListTrashOutcome, RestoreRoomOutcome};
+use crate::trash::TrashConfig;
+use crate::types::TrashedRoom;
+
+#[derive(Serialize)]
+pub enum ListTrashStatus {
+    Success(Vec<TrashedRoom>),
+    InternalServerError,
+    NotLoggedIn,
+}
+
+#[derive(Serialize)]
+pub enum RestoreRoomStatus {
+    Success,
+    InternalServerError,
+    NotLoggedIn,
+    NotOwner,
+    NotInTrash,
+}
+
+/// Lists the deleted rooms the user owns and can still restore.
+pub async fn trash(
+    State(db): State<Database>,
+    State(trash_config): State<Arc<TrashConfig>>,
+    Credential(credential): Credential,
+) -> impl IntoResponse {
+    let Some(credential) = credential else {
+        return (StatusCode::UNAUTHORIZED, Json(ListTrashStatus::NotLoggedIn));
+    };
+
+    match db.list_trash(credential, trash_config.retention).await {
+        Ok(ListTrashOutcome::Success(rooms)) => (StatusCode::OK, Json(ListTrashStatus::Success(rooms))),
+        Ok(ListTrashOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(ListTrashStatus::NotLoggedIn)),
+        Err(e) => {
+            eprintln!("List trash error: {e}");
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(ListTrashStatus::InternalServerError))
+        }
+    }
+}
+
+pub async fn restore(
+    State(db): State<Database>,
+    Credential(credential): Credential,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    let Some(credential) = credential else {
+        return (StatusCode::UNAUTHORIZED, Json(RestoreRoomStatus::NotLoggedIn));
+    };
+
+    match db.restore_room(credential, id).await {
+        Ok(RestoreRoomOutcome::Success) => (StatusCode::OK, Json(RestoreRoomStatus::Success)),
+        Ok(RestoreRoomOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(RestoreRoomStatus::NotLoggedIn)),
+        Ok(RestoreRoomOutcome::NotOwner) => (StatusCode::UNAUTHORIZED, Json(RestoreRoomStatus::NotOwner)),
+        Ok(RestoreRoomOutcome::NotInTrash) => (StatusCode::CONFLICT, Json(RestoreRoomStatus::NotInTrash)),
+        Err(e) => {
+            eprintln!("Restore room error: {e}");
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(RestoreRoomStatus::InternalServerError))
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use axum::{
+    extract::{Path, State, Json},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+
+use crate::credential::Credential;
+use crate::data::{Database, UpdateRoomOutcome};
+use crate::types::RoomUpdate;
+
+#[derive(Serialize)]
+pub enum UpdateRoomStatus {
+    Success,
+    InternalServerError,
+    NotLoggedIn,
+    NotOwner,
+    InvalidSettings,
+}
+
+pub async fn update(
+    State(db): State<Database>,
+    Credential(credential): Credential,
+    Path(id): Path<i32>,
+    Json(update): Json<RoomUpdate>,
+) -> impl IntoResponse {
+    if let Some(credential) = credential {
+        match db.update_room(credential, id, update).await {
+            Ok(UpdateRoomOutcome::Success) => (StatusCode::OK, Json(UpdateRoomStatus::Success)),
+            Ok(UpdateRoomOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(UpdateRoomStatus::NotLoggedIn)),
+            Ok(UpdateRoomOutcome::NotOwner) => (StatusCode::UNAUTHORIZED, Json(UpdateRoomStatus::NotOwner)),
+            Ok(UpdateRoomOutcome::InvalidSettings) => (StatusCode::BAD_REQUEST, Json(UpdateRoomStatus::InvalidSettings)),
+            Err(e) => {
+                eprintln!("Update room error: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(UpdateRoomStatus::InternalServerError))
+            }
+        }
+    } else {
+        (StatusCode::UNAUTHORIZED, Json(UpdateRoomStatus::NotLoggedIn))
+    }
+}
@@ -0,0 +1,134 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{multipart::Field, Multipart, Path, State, Json},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+
+use crate::credential::Credential;
+use crate::data::{Database, CanSubmitOutcome, CreateSubmissionOutcome};
+use crate::types::NewSubmissionFile;
+use crate::uploads::{self, UploadConfig};
+
+#[derive(Serialize)]
+pub enum CreateSubmissionStatus {
+    Success(i32),
+    InternalServerError,
+    NotLoggedIn,
+    AssignmentNotFound,
+    AlreadySubmitted,
+    RoomArchived,
+    NoFiles,
+    TooManyFiles,
+    FileTooLarge,
+    UnsupportedContentType,
+    InvalidUpload,
+}
+
+type Rejection = (StatusCode, Json<CreateSubmissionStatus>);
+
+pub async fn create(
+    State(db): State<Database>,
+    State(upload_config): State<Arc<UploadConfig>>,
+    Credential(credential): Credential,
+    Path(assignment_id): Path<i32>,
+    multipart: Multipart,
+) -> impl IntoResponse {
+    let Some(credential) = credential else {
+        return (StatusCode::UNAUTHORIZED, Json(CreateSubmissionStatus::NotLoggedIn));
+    };
+
+    // Turn the request away before buffering a body that would be rejected anyway
+    match db.can_submit(credential.clone(), assignment_id).await {
+        Ok(CanSubmitOutcome::Allowed) => {}
+        Ok(CanSubmitOutcome::NotLoggedIn) => return (StatusCode::UNAUTHORIZED, Json(CreateSubmissionStatus::NotLoggedIn)),
+        Ok(CanSubmitOutcome::AssignmentNotFound) => return (StatusCode::NOT_FOUND, Json(CreateSubmissionStatus::AssignmentNotFound)),
+        Ok(CanSubmitOutcome::AlreadySubmitted) => return (StatusCode::CONFLICT, Json(CreateSubmissionStatus::AlreadySubmitted)),
+        Ok(CanSubmitOutcome::RoomArchived) => return (StatusCode::CONFLICT, Json(CreateSubmissionStatus::RoomArchived)),
+        Err(e) => {
+            eprintln!("Create submission error: {e}");
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(CreateSubmissionStatus::InternalServerError));
+        }
+    }
+
+    let files = match read_files(&upload_config, multipart).await {
+        Ok(files) => files,
+        Err(rejection) => return rejection,
+    };
+
+    match db.create_submission(credential, assignment_id, files, &upload_config.dir).await {
+        Ok(CreateSubmissionOutcome::Success(id)) => (StatusCode::OK, Json(CreateSubmissionStatus::Success(id))),
+        Ok(CreateSubmissionOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(CreateSubmissionStatus::NotLoggedIn)),
+        Ok(CreateSubmissionOutcome::AssignmentNotFound) => (StatusCode::NOT_FOUND, Json(CreateSubmissionStatus::AssignmentNotFound)),
+        Ok(CreateSubmissionOutcome::AlreadySubmitted) => (StatusCode::CONFLICT, Json(CreateSubmissionStatus::AlreadySubmitted)),
+        Ok(CreateSubmissionOutcome::RoomArchived) => (StatusCode::CONFLICT, Json(CreateSubmissionStatus::RoomArchived)),
+        Err(e) => {
+            eprintln!("Create submission error: {e}");
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(CreateSubmissionStatus::InternalServerError))
+        }
+    }
+}
+
+/// Reads every file field of the multipart body, enforcing the configured limits.
+///
+/// Fields without a file name are ignored. Stops at the first field that breaks a limit,
+/// leaving the rest of the body unread.
+async fn read_files(
+    upload_config: &UploadConfig,
+    mut multipart: Multipart,
+) -> Result<Vec<NewSubmissionFile>, Rejection> {
+    let mut files = Vec::new();
+
+    while let Some(field) = multipart.next_field().await.map_err(multipart_rejection)? {
+        let Some(file_name) = field.file_name().map(uploads::sanitize_file_name) else {
+            continue;
+        };
+
+        if files.len() >= upload_config.max_files {
+            return Err((StatusCode::PAYLOAD_TOO_LARGE, Json(CreateSubmissionStatus::TooManyFiles)));
+        }
+
+        let content_type = field.content_type().unwrap_or_default().to_string();
+        if !upload_config.is_allowed_content_type(&content_type) {
+            return Err((StatusCode::UNSUPPORTED_MEDIA_TYPE, Json(CreateSubmissionStatus::UnsupportedContentType)));
+        }
+
+        let data = read_field(upload_config, field).await?;
+
+        files.push(NewSubmissionFile {
+            file_name,
+            content_type,
+            data,
+        });
+    }
+
+    if files.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, Json(CreateSubmissionStatus::NoFiles)));
+    }
+
+    Ok(files)
+}
+
+async fn read_field(upload_config: &UploadConfig, mut field: Field<'_>) -> Result<Vec<u8>, Rejection> {
+    let mut data = Vec::new();
+
+    while let Some(chunk) = field.chunk().await.map_err(multipart_rejection)? {
+        if data.len() + chunk.len() > upload_config.max_file_size {
+            return Err((StatusCode::PAYLOAD_TOO_LARGE, Json(CreateSubmissionStatus::FileTooLarge)));
+        }
+
+        data.extend_from_slice(&chunk);
+    }
+
+    Ok(data)
+}
+
+fn multipart_rejection(e: axum::extract::multipart::MultipartError) -> Rejection {
+    if e.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        (StatusCode::PAYLOAD_TOO_LARGE, Json(CreateSubmissionStatus::FileTooLarge))
+    } else {
+        (StatusCode::BAD_REQUEST, Json(CreateSubmissionStatus::InvalidUpload))
+    }
+}
@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State, Json},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+
+use crate::credential::Credential;
+use crate::data::{Database, GetSubmissionFileOutcome};
+use crate::uploads::UploadConfig;
+
+#[derive(Serialize)]
+pub enum GetSubmissionFileStatus {
+    InternalServerError,
+    NotLoggedIn,
+    NotFound,
+}
+
+pub async fn file(
+    State(db): State<Database>,
+    State(upload_config): State<Arc<UploadConfig>>,
+    Credential(credential): Credential,
+    Path((id, file_id)): Path<(i32, i32)>,
+) -> Response {
+    let Some(credential) = credential else {
+        return (StatusCode::UNAUTHORIZED, Json(GetSubmissionFileStatus::NotLoggedIn)).into_response();
+    };
+
+    match db.get_submission_file(credential, id, file_id, &upload_config.dir).await {
+        Ok(GetSubmissionFileOutcome::Success(file)) => match tokio::fs::read(&file.path).await {
+            Ok(data) => {
+                // Keep the header plain ASCII and quoted, the original name is still available in the metadata
+                let file_name: String = file
+                    .file_name
+                    .chars()
+                    .map(|c| if c.is_ascii() && c != '"' && c != '\\' { c } else { '_' })
+                    .collect();
+
+                (
+                    StatusCode::OK,
+                    [
+                        (header::CONTENT_TYPE, file.content_type),
+                        (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{file_name}\"")),
+                        // The content type is whatever the submitter claimed, browsers must not guess another
+                        (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+                    ],
+                    data,
+                )
+                    .into_response()
+            }
+            Err(e) => {
+                eprintln!("Read submission file error: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(GetSubmissionFileStatus::InternalServerError)).into_response()
+            }
+        },
+        Ok(GetSubmissionFileOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(GetSubmissionFileStatus::NotLoggedIn)).into_response(),
+        Ok(GetSubmissionFileOutcome::NotFound) => (StatusCode::NOT_FOUND, Json(GetSubmissionFileStatus::NotFound)).into_response(),
+        Err(e) => {
+            eprintln!("Get submission file error: {e}");
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(GetSubmissionFileStatus::InternalServerError)).into_response()
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use axum::{
+    extract::{Path, State, Json},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+
+use crate::credential::Credential;
+use crate::data::{Database, GetSubmissionOutcome};
+use crate::types::Submission;
+
+#[derive(Serialize)]
+pub enum GetSubmissionStatus {
+    Success(Submission),
+    InternalServerError,
+    NotLoggedIn,
+    NotFound,
+}
+
+pub async fn get(
+    State(db): State<Database>,
+    Credential(credential): Credential,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    if let Some(credential) = credential {
+        match db.get_submission(credential, id).await {
+            Ok(GetSubmissionOutcome::Success(submission)) => (StatusCode::OK, Json(GetSubmissionStatus::Success(submission))),
+            Ok(GetSubmissionOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(GetSubmissionStatus::NotLoggedIn)),
+            Ok(GetSubmissionOutcome::NotFound) => (StatusCode::NOT_FOUND, Json(GetSubmissionStatus::NotFound)),
+            Err(e) => {
+                eprintln!("Get submission error: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(GetSubmissionStatus::InternalServerError))
+            }
+        }
+    } else {
+        (StatusCode::UNAUTHORIZED, Json(GetSubmissionStatus::NotLoggedIn))
+    }
+}
@@ -0,0 +1,38 @@
+use axum::{
+    extract::{Path, State, Json},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+
+use crate::credential::Credential;
+use crate::data::{Database, ListSubmissionsOutcome};
+use crate::types::Submission;
+
+#[derive(Serialize)]
+pub enum ListSubmissionsStatus {
+    Success(Vec<Submission>),
+    InternalServerError,
+    NotLoggedIn,
+    AssignmentNotFound,
+}
+
+pub async fn list(
+    State(db): State<Database>,
+    Credential(credential): Credential,
+    Path(assignment_id): Path<i32>,
+) -> impl IntoResponse {
+    if let Some(credential) = credential {
+        match db.list_submissions(credential, assignment_id).await {
+            Ok(ListSubmissionsOutcome::Success(submissions)) => (StatusCode::OK, Json(ListSubmissionsStatus::Success(submissions))),
+            Ok(ListSubmissionsOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(ListSubmissionsStatus::NotLoggedIn)),
+            Ok(ListSubmissionsOutcome::AssignmentNotFound) => (StatusCode::NOT_FOUND, Json(ListSubmissionsStatus::AssignmentNotFound)),
+            Err(e) => {
+                eprintln!("List submissions error: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(ListSubmissionsStatus::InternalServerError))
+            }
+        }
+    } else {
+        (StatusCode::UNAUTHORIZED, Json(ListSubmissionsStatus::NotLoggedIn))
+    }
+}
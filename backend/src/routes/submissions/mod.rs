@@ -0,0 +1,11 @@
+mod create;
+pub use create::create;
+
+mod file;
+pub use file::file;
+
+mod get;
+pub use get::get;
+
+mod list;
+pub use list::list;
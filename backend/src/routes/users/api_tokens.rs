@@ -0,0 +1,92 @@
+use axum::{
+    extract::{Path, State, Json},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+use tower_cookies::Cookies;
+
+use crate::data::{CreateApiTokenOutcome, Database, ListApiTokensOutcome, RevokeApiTokenOutcome};
+use crate::types::{ApiToken, CreatedApiToken, NewApiToken};
+
+#[derive(Serialize)]
+pub enum CreateApiTokenStatus {
+    /// The token itself is shown only this once
+    Success(CreatedApiToken),
+    InternalServerError,
+    NotLoggedIn,
+    InvalidSettings,
+}
+
+#[derive(Serialize)]
+pub enum ListApiTokensStatus {
+    Success(Vec<ApiToken>),
+    InternalServerError,
+    NotLoggedIn,
+}
+
+#[derive(Serialize)]
+pub enum RevokeApiTokenStatus {
+    Success,
+    InternalServerError,
+    NotLoggedIn,
+    TokenNotFound,
+}
+
+pub async fn create_api_token(
+    State(db): State<Database>,
+    cookies: Cookies,
+    Json(settings): Json<NewApiToken>,
+) -> impl IntoResponse {
+    if let Some(session_uuid_cookie) = cookies.get("session_uuid") {
+        match db.create_api_token(session_uuid_cookie.value().to_string(), settings).await {
+            Ok(CreateApiTokenOutcome::Success(token)) => (StatusCode::OK, Json(CreateApiTokenStatus::Success(token))),
+            Ok(CreateApiTokenOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(CreateApiTokenStatus::NotLoggedIn)),
+            Ok(CreateApiTokenOutcome::InvalidSettings) => (StatusCode::BAD_REQUEST, Json(CreateApiTokenStatus::InvalidSettings)),
+            Err(e) => {
+                eprintln!("Create API token error: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(CreateApiTokenStatus::InternalServerError))
+            }
+        }
+    } else {
+        (StatusCode::UNAUTHORIZED, Json(CreateApiTokenStatus::NotLoggedIn))
+    }
+}
+
+pub async fn api_tokens(
+    State(db): State<Database>,
+    cookies: Cookies,
+) -> impl IntoResponse {
+    if let Some(session_uuid_cookie) = cookies.get("session_uuid") {
+        match db.list_api_tokens(session_uuid_cookie.value().to_string()).await {
+            Ok(ListApiTokensOutcome::Success(tokens)) => (StatusCode::OK, Json(ListApiTokensStatus::Success(tokens))),
+            Ok(ListApiTokensOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(ListApiTokensStatus::NotLoggedIn)),
+            Err(e) => {
+                eprintln!("List API tokens error: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(ListApiTokensStatus::InternalServerError))
+            }
+        }
+    } else {
+        (StatusCode::UNAUTHORIZED, Json(ListApiTokensStatus::NotLoggedIn))
+    }
+}
+
+pub async fn revoke_api_token(
+    State(db): State<Database>,
+    cookies: Cookies,
+    Path(id): Path<i64>,
+) -> impl IntoResponse {
+    if let Some(session_uuid_cookie) = cookies.get("session_uuid") {
+        match db.revoke_api_token(session_uuid_cookie.value().to_string(), id).await {
+            Ok(RevokeApiTokenOutcome::Success) => (StatusCode::OK, Json(RevokeApiTokenStatus::Success)),
+            Ok(RevokeApiTokenOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(RevokeApiTokenStatus::NotLoggedIn)),
+            Ok(RevokeApiTokenOutcome::TokenNotFound) => (StatusCode::NOT_FOUND, Json(RevokeApiTokenStatus::TokenNotFound)),
+            Err(e) => {
+                eprintln!("Revoke API token error: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(RevokeApiTokenStatus::InternalServerError))
+            }
+        }
+    } else {
+        (StatusCode::UNAUTHORIZED, Json(RevokeApiTokenStatus::NotLoggedIn))
+    }
+}
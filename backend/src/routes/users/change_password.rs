@@ -0,0 +1,38 @@
+use axum::{
+    extract::{State, Json},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+use tower_cookies::Cookies;
+
+use crate::data::{ChangePasswordOutcome, Database};
+use crate::types::PasswordChange;
+
+#[derive(Serialize)]
+pub enum ChangePasswordStatus {
+    Success,
+    InternalServerError,
+    NotLoggedIn,
+    InvalidCredentials,
+}
+
+pub async fn change_password(
+    State(db): State<Database>,
+    cookies: Cookies,
+    Json(change): Json<PasswordChange>,
+) -> impl IntoResponse {
+    if let Some(session_uuid_cookie) = cookies.get("session_uuid") {
+        match db.change_password(session_uuid_cookie.value().to_string(), change).await {
+            Ok(ChangePasswordOutcome::Success) => (StatusCode::OK, Json(ChangePasswordStatus::Success)),
+            Ok(ChangePasswordOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(ChangePasswordStatus::NotLoggedIn)),
+            Ok(ChangePasswordOutcome::InvalidCredentials) => (StatusCode::FORBIDDEN, Json(ChangePasswordStatus::InvalidCredentials)),
+            Err(e) => {
+                eprintln!("Change password error: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(ChangePasswordStatus::InternalServerError))
+            }
+        }
+    } else {
+        (StatusCode::UNAUTHORIZED, Json(ChangePasswordStatus::NotLoggedIn))
+    }
+}
@@ -0,0 +1,35 @@
+use axum::{
+    extract::{State, Json},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+
+use crate::credential::Credential;
+use crate::data::{Database, GetCurrentUserOutcome};
+use crate::types::CurrentUser;
+
+#[derive(Serialize)]
+pub enum GetCurrentUserStatus {
+    Success(CurrentUser),
+    InternalServerError,
+    NotLoggedIn,
+}
+
+pub async fn me(
+    State(db): State<Database>,
+    Credential(credential): Credential,
+) -> impl IntoResponse {
+    if let Some(credential) = credential {
+        match db.get_current_user(credential).await {
+            Ok(GetCurrentUserOutcome::Success(user)) => (StatusCode::OK, Json(GetCurrentUserStatus::Success(user))),
+            Ok(GetCurrentUserOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(GetCurrentUserStatus::NotLoggedIn)),
+            Err(e) => {
+                eprintln!("Get current user error: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(GetCurrentUserStatus::InternalServerError))
+            }
+        }
+    } else {
+        (StatusCode::UNAUTHORIZED, Json(GetCurrentUserStatus::NotLoggedIn))
+    }
+}
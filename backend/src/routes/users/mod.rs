@@ -0,0 +1,14 @@
+mod api_tokens;
+pub use api_tokens::{api_tokens, create_api_token, revoke_api_token};
+
+mod change_password;
+pub use change_password::change_password;
+
+mod me;
+pub use me::me;
+
+mod totp;
+pub use totp::{confirm_totp_enrollment, disable_totp, start_totp_enrollment};
+
+mod update_profile;
+pub use update_profile::update_profile;
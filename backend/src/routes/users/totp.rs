@@ -0,0 +1,100 @@
+use axum::{
+    extract::{State, Json},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+use tower_cookies::Cookies;
+
+use crate::data::{ConfirmTotpEnrollmentOutcome, Database, DisableTotpOutcome, StartTotpEnrollmentOutcome};
+use crate::types::{DisableTotp, TotpCode, TotpEnrollment};
+
+#[derive(Serialize)]
+pub enum StartTotpEnrollmentStatus {
+    Success(TotpEnrollment),
+    InternalServerError,
+    NotLoggedIn,
+    AlreadyEnabled,
+}
+
+#[derive(Serialize)]
+pub enum ConfirmTotpEnrollmentStatus {
+    /// Recovery codes, shown only this once
+    Success(Vec<String>),
+    InternalServerError,
+    NotLoggedIn,
+    NotStarted,
+    AlreadyEnabled,
+    InvalidCode,
+}
+
+#[derive(Serialize)]
+pub enum DisableTotpStatus {
+    Success,
+    InternalServerError,
+    NotLoggedIn,
+    NotEnabled,
+    InvalidCredentials,
+}
+
+pub async fn start_totp_enrollment(
+    State(db): State<Database>,
+    cookies: Cookies,
+) -> impl IntoResponse {
+    if let Some(session_uuid_cookie) = cookies.get("session_uuid") {
+        match db.start_totp_enrollment(session_uuid_cookie.value().to_string()).await {
+            Ok(StartTotpEnrollmentOutcome::Success(enrollment)) => (StatusCode::OK, Json(StartTotpEnrollmentStatus::Success(enrollment))),
+            Ok(StartTotpEnrollmentOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(StartTotpEnrollmentStatus::NotLoggedIn)),
+            Ok(StartTotpEnrollmentOutcome::AlreadyEnabled) => (StatusCode::CONFLICT, Json(StartTotpEnrollmentStatus::AlreadyEnabled)),
+            Err(e) => {
+                eprintln!("Start TOTP enrollment error: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(StartTotpEnrollmentStatus::InternalServerError))
+            }
+        }
+    } else {
+        (StatusCode::UNAUTHORIZED, Json(StartTotpEnrollmentStatus::NotLoggedIn))
+    }
+}
+
+pub async fn confirm_totp_enrollment(
+    State(db): State<Database>,
+    cookies: Cookies,
+    Json(code): Json<TotpCode>,
+) -> impl IntoResponse {
+    if let Some(session_uuid_cookie) = cookies.get("session_uuid") {
+        match db.confirm_totp_enrollment(session_uuid_cookie.value().to_string(), code.code).await {
+            Ok(ConfirmTotpEnrollmentOutcome::Success(recovery_codes)) => (StatusCode::OK, Json(ConfirmTotpEnrollmentStatus::Success(recovery_codes))),
+            Ok(ConfirmTotpEnrollmentOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(ConfirmTotpEnrollmentStatus::NotLoggedIn)),
+            Ok(ConfirmTotpEnrollmentOutcome::NotStarted) => (StatusCode::CONFLICT, Json(ConfirmTotpEnrollmentStatus::NotStarted)),
+            Ok(ConfirmTotpEnrollmentOutcome::AlreadyEnabled) => (StatusCode::CONFLICT, Json(ConfirmTotpEnrollmentStatus::AlreadyEnabled)),
+            Ok(ConfirmTotpEnrollmentOutcome::InvalidCode) => (StatusCode::BAD_REQUEST, Json(ConfirmTotpEnrollmentStatus::InvalidCode)),
+            Err(e) => {
+                eprintln!("Confirm TOTP enrollment error: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(ConfirmTotpEnrollmentStatus::InternalServerError))
+            }
+        }
+    } else {
+        (StatusCode::UNAUTHORIZED, Json(ConfirmTotpEnrollmentStatus::NotLoggedIn))
+    }
+}
+
+pub async fn disable_totp(
+    State(db): State<Database>,
+    cookies: Cookies,
+    Json(disable): Json<DisableTotp>,
+) -> impl IntoResponse {
+    if let Some(session_uuid_cookie) = cookies.get("session_uuid") {
+        match db.disable_totp(session_uuid_cookie.value().to_string(), disable.password).await {
+            Ok(DisableTotpOutcome::Success) => (StatusCode::OK, Json(DisableTotpStatus::Success)),
+            Ok(DisableTotpOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(DisableTotpStatus::NotLoggedIn)),
+            Ok(DisableTotpOutcome::NotEnabled) => (StatusCode::CONFLICT, Json(DisableTotpStatus::NotEnabled)),
+            Ok(DisableTotpOutcome::InvalidCredentials) => (StatusCode::FORBIDDEN, Json(DisableTotpStatus::InvalidCredentials)),
+            Err(e) => {
+                eprintln!("Disable TOTP error: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(DisableTotpStatus::InternalServerError))
+            }
+        }
+    } else {
+        (StatusCode::UNAUTHORIZED, Json(DisableTotpStatus::NotLoggedIn))
+    }
+}
@@ -0,0 +1,54 @@
+use axum::{
+    extract::{State, Json},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Serialize;
+use std::sync::Arc;
+use tower_cookies::Cookies;
+
+use crate::data::{Database, UpdateProfileOutcome};
+use crate::mailer::Mailer;
+use crate::routes::auth::send_verification_email;
+use crate::types::ProfileUpdate;
+use crate::verification::EmailVerification;
+
+#[derive(Serialize)]
+pub enum UpdateProfileStatus {
+    Success,
+    InternalServerError,
+    NotLoggedIn,
+    EmailAlreadyInUse,
+    InvalidCredentials,
+}
+
+pub async fn update_profile(
+    State(db): State<Database>,
+    State(mailer): State<Arc<Mailer>>,
+    State(verification): State<Arc<EmailVerification>>,
+    cookies: Cookies,
+    Json(update): Json<ProfileUpdate>,
+) -> impl IntoResponse {
+    if let Some(session_uuid_cookie) = cookies.get("session_uuid") {
+        let email = update.email.clone();
+
+        match db.update_profile(session_uuid_cookie.value().to_string(), update).await {
+            Ok(UpdateProfileOutcome::Success) => (StatusCode::OK, Json(UpdateProfileStatus::Success)),
+            Ok(UpdateProfileOutcome::EmailChanged(user_id)) => {
+                if let Some(email) = email {
+                    send_verification_email(mailer, &verification, user_id, email);
+                }
+                (StatusCode::OK, Json(UpdateProfileStatus::Success))
+            }
+            Ok(UpdateProfileOutcome::NotLoggedIn) => (StatusCode::UNAUTHORIZED, Json(UpdateProfileStatus::NotLoggedIn)),
+            Ok(UpdateProfileOutcome::EmailAlreadyInUse) => (StatusCode::CONFLICT, Json(UpdateProfileStatus::EmailAlreadyInUse)),
+            Ok(UpdateProfileOutcome::InvalidCredentials) => (StatusCode::FORBIDDEN, Json(UpdateProfileStatus::InvalidCredentials)),
+            Err(e) => {
+                eprintln!("Update profile error: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(UpdateProfileStatus::InternalServerError))
+            }
+        }
+    } else {
+        (StatusCode::UNAUTHORIZED, Json(UpdateProfileStatus::NotLoggedIn))
+    }
+}
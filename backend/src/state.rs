@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use axum::extract::FromRef;
+
+use crate::data::Database;
+use crate::mailer::Mailer;
+use crate::oidc::Oidc;
+use crate::registration::RegistrationConfig;
+use crate::trash::TrashConfig;
+use crate::uploads::UploadConfig;
+use crate::verification::EmailVerification;
+
+/// Shared state handed to every route.
+///
+/// Handlers extract only the parts they need, e.g. `State<Database>`.
+#[derive(Clone, FromRef)]
+pub struct AppState {
+    pub db: Database,
+    pub uploads: Arc<UploadConfig>,
+    pub mailer: Arc<Mailer>,
+    pub verification: Arc<EmailVerification>,
+    pub registration: Arc<RegistrationConfig>,
+    pub trash: Arc<TrashConfig>,
+    /// `None` if single sign-on is not configured
+    pub oidc: Option<Arc<Oidc>>,
+}
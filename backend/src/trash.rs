@@ -0,0 +1,32 @@
+use std::env;
+
+const DEFAULT_RETENTION_DAYS: i64 = 30;
+
+#[derive(Clone)]
+pub struct TrashConfig {
+    /// How long deleted rooms can be restored before they are purged, in seconds
+    pub retention: i64,
+}
+
+impl TrashConfig {
+    /// Reads the trash configuration from the environment.
+    ///
+    /// - `ROOM_TRASH_RETENTION_DAYS`: days a deleted room stays in the trash before it and
+    ///   everything in it is removed for good (default 30)
+    pub fn from_env() -> Self {
+        let days = match env::var("ROOM_TRASH_RETENTION_DAYS") {
+            Ok(days) => match days.parse::<i64>() {
+                Ok(days) if days >= 0 => days,
+                _ => {
+                    println!("WARN: invalid ROOM_TRASH_RETENTION_DAYS {days:?}, keeping deleted rooms for {DEFAULT_RETENTION_DAYS} days");
+                    DEFAULT_RETENTION_DAYS
+                }
+            },
+            Err(_) => DEFAULT_RETENTION_DAYS,
+        };
+
+        Self {
+            retention: days.saturating_mul(24 * 60 * 60),
+        }
+    }
+}
@@ -1,4 +1,7 @@
+use deadpool_sqlite::rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tokio::sync::mpsc;
 
 #[derive(Deserialize)]
 pub struct NewUser {
@@ -8,6 +11,149 @@ pub struct NewUser {
     pub email: String,
 }
 
+/// The logged in user, as shown to themselves
+#[derive(Serialize)]
+pub struct CurrentUser {
+    pub id: i64,
+    pub name: String,
+    pub surname: String,
+    pub email: String,
+    pub email_verified: bool,
+    pub rooms: Vec<RoomMembership>,
+}
+
+#[derive(Serialize)]
+pub struct RoomMembership {
+    pub room_id: i32,
+    pub name: String,
+    pub role: RoomRole,
+}
+
+/// Fields left out are kept as they are
+#[derive(Deserialize)]
+pub struct ProfileUpdate {
+    pub name: Option<String>,
+    pub surname: Option<String>,
+    pub email: Option<String>,
+    /// Required when the email changes
+    pub current_password: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct PasswordChange {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+/// A freshly generated TOTP secret, to be added to an authenticator app
+#[derive(Serialize)]
+pub struct TotpEnrollment {
+    /// Base32 encoded, for typing into the app by hand
+    pub secret: String,
+    /// `otpauth://` URI, usually shown as a QR code
+    pub otpauth_uri: String,
+}
+
+#[derive(Deserialize)]
+pub struct TotpCode {
+    pub code: String,
+}
+
+#[derive(Deserialize)]
+pub struct DisableTotp {
+    pub password: String,
+}
+
+/// Second step of a login for users with TOTP enabled
+#[derive(Deserialize)]
+pub struct TotpLogin {
+    pub challenge: String,
+    /// Either a TOTP code or a recovery code
+    pub code: String,
+}
+
+#[derive(Deserialize)]
+pub struct NewApiToken {
+    /// Reminds the user what the token is for
+    pub name: String,
+    pub scope: ApiTokenScope,
+    /// Seconds until the token stops working, 90 days if absent
+    pub expires_in: Option<i64>,
+}
+
+/// Shown only once, right after the token was created
+#[derive(Serialize)]
+pub struct CreatedApiToken {
+    pub id: i64,
+    pub token: String,
+    pub expires_at: i64,
+}
+
+#[derive(Serialize)]
+pub struct ApiToken {
+    pub id: i64,
+    pub name: String,
+    pub scope: ApiTokenScope,
+    pub created_at: i64,
+    pub last_used_at: Option<i64>,
+    pub expires_at: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ApiTokenScope {
+    /// Only `GET` requests
+    Read,
+    /// Everything but managing sessions, API tokens and the account's credentials
+    Write,
+}
+
+impl ToSql for ApiTokenScope {
+    fn to_sql(&self) -> deadpool_sqlite::rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(match self {
+            Self::Read => "read",
+            Self::Write => "write",
+        }
+        .into())
+    }
+}
+
+impl FromSql for ApiTokenScope {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value.as_str()? {
+            "read" => Ok(Self::Read),
+            "write" => Ok(Self::Write),
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+}
+
+/// A user as vouched for by an OpenID Connect identity provider
+pub struct OidcIdentity {
+    pub issuer: String,
+    /// The provider's stable ID for the user, unlike the email it never changes
+    pub subject: String,
+    pub email: String,
+    pub email_verified: bool,
+    pub name: String,
+    pub surname: String,
+}
+
+#[derive(Deserialize)]
+pub struct PasswordResetRequest {
+    pub email: String,
+}
+
+#[derive(Deserialize)]
+pub struct PasswordResetConfirmation {
+    pub token: String,
+    pub password: String,
+}
+
+#[derive(Deserialize)]
+pub struct ResendVerificationEmail {
+    pub email: String,
+}
+
 #[derive(Debug)]
 pub struct User {
     pub id: i32,
@@ -17,10 +163,24 @@ pub struct User {
     pub email: String,
 }
 
+#[derive(Serialize)]
+pub struct Session {
+    pub id: i64,
+    pub created_at: i64,
+    pub last_seen_at: i64,
+    pub expires_at: i64,
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
+    /// Whether this is the session making the request
+    pub current: bool,
+}
+
 #[derive(Deserialize)]
 pub struct NewRoom {
     pub name: String,
     pub description: String,
+    #[serde(default)]
+    pub join_approval_required: bool,
 }
 
 #[derive(Serialize)]
@@ -28,4 +188,309 @@ pub struct Room {
     pub id: i32,
     pub name: String,
     pub description: String,
+    pub join_approval_required: bool,
+    /// Set once the room has been archived, its content is read-only from then on
+    pub archived_at: Option<i64>,
+    pub visibility: RoomVisibility,
+    /// `#rrggbb`
+    pub color: Option<String>,
+    pub section: Option<String>,
+    pub term: Option<String>,
+    /// Role of the requesting user in this room
+    pub role: RoomRole,
+}
+
+/// Changes to a room's name, description and settings, fields that are `None` stay as they are.
+#[derive(Deserialize)]
+pub struct RoomUpdate {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub join_approval_required: Option<bool>,
+    pub visibility: Option<RoomVisibility>,
+    /// `#rrggbb`, an empty string removes the colour
+    pub color: Option<String>,
+    /// An empty string removes the section
+    pub section: Option<String>,
+    /// An empty string removes the term
+    pub term: Option<String>,
+}
+
+/// Who sees a room and what is in it, hidden rooms can be prepared before students see them.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum RoomVisibility {
+    /// Every member sees the room
+    Visible,
+    /// Only owners and teachers see the room
+    Hidden,
+}
+
+impl ToSql for RoomVisibility {
+    fn to_sql(&self) -> deadpool_sqlite::rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(match self {
+            Self::Visible => "visible",
+            Self::Hidden => "hidden",
+        }
+        .into())
+    }
+}
+
+impl FromSql for RoomVisibility {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value.as_str()? {
+            "visible" => Ok(Self::Visible),
+            "hidden" => Ok(Self::Hidden),
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+}
+
+/// A deleted room its owner can still restore.
+#[derive(Serialize)]
+pub struct TrashedRoom {
+    pub id: i32,
+    pub name: String,
+    pub description: String,
+    pub deleted_at: i64,
+    /// When the room and everything in it will be removed for good
+    pub purge_at: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum RoomRole {
+    Owner,
+    Teacher,
+    Student,
+}
+
+impl ToSql for RoomRole {
+    fn to_sql(&self) -> deadpool_sqlite::rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(match self {
+            Self::Owner => "owner",
+            Self::Teacher => "teacher",
+            Self::Student => "student",
+        }
+        .into())
+    }
+}
+
+impl FromSql for RoomRole {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value.as_str()? {
+            "owner" => Ok(Self::Owner),
+            "teacher" => Ok(Self::Teacher),
+            "student" => Ok(Self::Student),
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+pub struct NewInvitationCode {
+    /// Seconds until the code stops working, never expires if absent
+    pub expires_in: Option<i64>,
+    /// Number of times the code can be used, unlimited if absent
+    pub max_uses: Option<i32>,
+}
+
+#[derive(Serialize)]
+pub struct InvitationCode {
+    pub code: String,
+    /// Unix timestamp in seconds
+    pub created_at: i64,
+    /// Unix timestamp in seconds
+    pub expires_at: Option<i64>,
+    pub max_uses: Option<i32>,
+    pub uses: i32,
+}
+
+#[derive(Deserialize)]
+pub struct JoinApproval {
+    pub required: bool,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum JoinRequestStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+impl ToSql for JoinRequestStatus {
+    fn to_sql(&self) -> deadpool_sqlite::rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(match self {
+            Self::Pending => "pending",
+            Self::Approved => "approved",
+            Self::Rejected => "rejected",
+        }
+        .into())
+    }
+}
+
+impl FromSql for JoinRequestStatus {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value.as_str()? {
+            "pending" => Ok(Self::Pending),
+            "approved" => Ok(Self::Approved),
+            "rejected" => Ok(Self::Rejected),
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct JoinRequest {
+    pub room_id: i32,
+    pub room_name: String,
+    pub user_id: i32,
+    pub name: String,
+    pub surname: String,
+    pub email: String,
+    pub status: JoinRequestStatus,
+    /// Unix timestamp in seconds
+    pub requested_at: i64,
+    /// Unix timestamp in seconds
+    pub decided_at: Option<i64>,
+}
+
+#[derive(Serialize)]
+pub struct RoomMember {
+    pub user_id: i32,
+    pub name: String,
+    pub surname: String,
+    pub email: String,
+    pub role: RoomRole,
+}
+
+#[derive(Deserialize)]
+pub struct NewMemberRole {
+    pub role: RoomRole,
+}
+
+#[derive(Deserialize)]
+pub struct TransferOwnership {
+    pub user_id: i32,
+}
+
+#[derive(Deserialize)]
+pub struct NewAssignment {
+    pub title: String,
+    pub instructions: String,
+    /// Unix timestamp in seconds
+    pub due_date: Option<i64>,
+    pub max_points: i32,
+}
+
+#[derive(Serialize)]
+pub struct Assignment {
+    pub id: i32,
+    pub room_id: i32,
+    pub title: String,
+    pub instructions: String,
+    /// Unix timestamp in seconds
+    pub due_date: Option<i64>,
+    pub max_points: i32,
+}
+
+pub struct NewSubmissionFile {
+    pub file_name: String,
+    pub content_type: String,
+    pub data: Vec<u8>,
+}
+
+#[derive(Serialize)]
+pub struct Submission {
+    pub id: i32,
+    pub assignment_id: i32,
+    pub user_id: i32,
+    /// Unix timestamp in seconds
+    pub submitted_at: i64,
+    pub files: Vec<SubmissionFile>,
+}
+
+#[derive(Serialize)]
+pub struct SubmissionFile {
+    pub id: i32,
+    pub file_name: String,
+    pub content_type: String,
+    pub size: i64,
+}
+
+pub struct StoredFile {
+    pub file_name: String,
+    pub content_type: String,
+    pub path: PathBuf,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum GradeState {
+    /// Graded, but only visible to the room owner
+    Graded,
+    /// Graded and handed back to the student
+    Returned,
+}
+
+impl ToSql for GradeState {
+    fn to_sql(&self) -> deadpool_sqlite::rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(match self {
+            Self::Graded => "graded",
+            Self::Returned => "returned",
+        }
+        .into())
+    }
+}
+
+impl FromSql for GradeState {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value.as_str()? {
+            "graded" => Ok(Self::Graded),
+            "returned" => Ok(Self::Returned),
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct NewGrade {
+    pub points: f64,
+    #[serde(default)]
+    pub feedback: String,
+    pub state: GradeState,
+}
+
+#[derive(Serialize)]
+pub struct Grade {
+    pub submission_id: i32,
+    pub assignment_id: i32,
+    pub assignment_title: String,
+    pub points: f64,
+    pub max_points: i32,
+    pub feedback: String,
+    pub state: GradeState,
+    /// Unix timestamp in seconds
+    pub graded_at: i64,
+}
+
+/// A room's gradebook, with the member rows produced lazily while they are read from the database.
+pub struct Gradebook {
+    pub assignments: Vec<Assignment>,
+    pub rows: mpsc::Receiver<anyhow::Result<GradebookRow>>,
+}
+
+#[derive(Serialize)]
+pub struct GradebookRow {
+    pub user_id: i32,
+    pub name: String,
+    pub surname: String,
+    pub email: String,
+    /// One cell per assignment, in the same order as [`Gradebook::assignments`]
+    pub grades: Vec<GradebookCell>,
+    pub total: f64,
+}
+
+#[derive(Serialize)]
+pub struct GradebookCell {
+    pub assignment_id: i32,
+    pub points: Option<f64>,
+    pub submitted: bool,
+    pub late: bool,
 }
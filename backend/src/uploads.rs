@@ -0,0 +1,97 @@
+use std::{env, path::PathBuf};
+
+const DEFAULT_DIR: &str = "uploads";
+const DEFAULT_MAX_FILE_SIZE: usize = 10 * 1024 * 1024;
+const DEFAULT_MAX_FILES: usize = 10;
+const DEFAULT_ALLOWED_CONTENT_TYPES: &[&str] = &[
+    "application/pdf",
+    "application/zip",
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    "image/jpeg",
+    "image/png",
+    "text/plain",
+];
+
+#[derive(Clone)]
+pub struct UploadConfig {
+    pub dir: PathBuf,
+    pub max_file_size: usize,
+    pub max_files: usize,
+    pub allowed_content_types: Vec<String>,
+}
+
+impl UploadConfig {
+    /// Reads the upload configuration from the environment.
+    ///
+    /// - `UPLOAD_DIR`: directory submitted files are stored in (default `uploads`)
+    /// - `UPLOAD_MAX_FILE_SIZE`: maximum size of a single file in bytes (default 10 MiB)
+    /// - `UPLOAD_MAX_FILES`: maximum number of files per submission (default 10)
+    /// - `UPLOAD_ALLOWED_TYPES`: comma separated list of accepted content types
+    pub fn from_env() -> Self {
+        let dir = env::var("UPLOAD_DIR").map_or_else(|_| PathBuf::from(DEFAULT_DIR), PathBuf::from);
+
+        let max_file_size = env::var("UPLOAD_MAX_FILE_SIZE")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_MAX_FILE_SIZE);
+
+        let max_files = env::var("UPLOAD_MAX_FILES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_MAX_FILES);
+
+        let allowed_content_types = env::var("UPLOAD_ALLOWED_TYPES").map_or_else(
+            |_| DEFAULT_ALLOWED_CONTENT_TYPES.iter().map(ToString::to_string).collect(),
+            |types| {
+                types
+                    .split(',')
+                    .map(|t| t.trim().to_ascii_lowercase())
+                    .filter(|t| !t.is_empty())
+                    .collect()
+            },
+        );
+
+        Self {
+            dir,
+            max_file_size,
+            max_files,
+            allowed_content_types,
+        }
+    }
+
+    /// Upper bound for the size of a whole multipart request body.
+    pub fn max_body_size(&self) -> usize {
+        // Leave some room for multipart boundaries and headers
+        self.max_file_size
+            .saturating_mul(self.max_files)
+            .saturating_add(64 * 1024)
+    }
+
+    pub fn is_allowed_content_type(&self, content_type: &str) -> bool {
+        let essence = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+
+        self.allowed_content_types.contains(&essence)
+    }
+}
+
+/// Strips any path components and control characters from a client supplied file name.
+pub fn sanitize_file_name(name: &str) -> String {
+    let base = name.rsplit(['/', '\\']).next().unwrap_or_default();
+
+    let sanitized: String = base
+        .chars()
+        .filter(|c| !c.is_control() && *c != '"')
+        .take(255)
+        .collect();
+
+    if sanitized.is_empty() || sanitized == "." || sanitized == ".." {
+        "file".to_string()
+    } else {
+        sanitized
+    }
+}
@@ -0,0 +1,95 @@
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use sha2::Sha256;
+use std::env;
+
+use crate::data::utils::now;
+
+/// How long an email verification link stays valid, in seconds.
+const TOKEN_TTL: i64 = 7 * 24 * 60 * 60;
+
+/// What users who have not verified their email yet are allowed to do.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum UnverifiedEmailPolicy {
+    /// Unverified users may do everything
+    Allow,
+    /// Unverified users may log in but not join rooms
+    NoJoin,
+    /// Unverified users may not log in at all
+    NoLogin,
+}
+
+pub struct EmailVerification {
+    secret: Vec<u8>,
+    pub policy: UnverifiedEmailPolicy,
+}
+
+impl EmailVerification {
+    /// Reads the email verification configuration from the environment.
+    ///
+    /// - `APP_SECRET`: key verification links are signed with, a random one is used if unset
+    ///   which invalidates every link on restart
+    /// - `UNVERIFIED_EMAIL_POLICY`: one of `allow` (default), `no-join` or `no-login`
+    pub fn from_env() -> Self {
+        let secret = env::var("APP_SECRET").map_or_else(
+            |_| {
+                println!("WARN: APP_SECRET is not set, verification links will not survive a restart");
+                rand::rng().random::<[u8; 32]>().to_vec()
+            },
+            String::into_bytes,
+        );
+
+        let policy = match env::var("UNVERIFIED_EMAIL_POLICY").as_deref() {
+            Ok("no-join") => UnverifiedEmailPolicy::NoJoin,
+            Ok("no-login") => UnverifiedEmailPolicy::NoLogin,
+            Ok("allow") | Err(_) => UnverifiedEmailPolicy::Allow,
+            Ok(other) => {
+                println!("WARN: unknown UNVERIFIED_EMAIL_POLICY {other:?}, allowing unverified users");
+                UnverifiedEmailPolicy::Allow
+            }
+        };
+
+        Self { secret, policy }
+    }
+
+    pub fn may_log_in_unverified(&self) -> bool {
+        self.policy != UnverifiedEmailPolicy::NoLogin
+    }
+
+    pub fn may_join_unverified(&self) -> bool {
+        self.policy == UnverifiedEmailPolicy::Allow
+    }
+
+    /// Creates a signed token proving that `email` belongs to the user with the given ID.
+    ///
+    /// Nothing is stored, the token carries its own expiry and stops working once the
+    /// user changes their email.
+    pub fn token(&self, user_id: i64, email: &str) -> String {
+        let payload = format!("{user_id}.{}.{}", now() + TOKEN_TTL, hex::encode(email));
+        let signature = hex::encode(self.mac(&payload).finalize().into_bytes());
+
+        format!("{payload}.{signature}")
+    }
+
+    /// Checks a token from [`EmailVerification::token`], returning the user ID and email it is for.
+    ///
+    /// Returns `None` if the token is malformed, forged or expired.
+    pub fn check(&self, token: &str) -> Option<(i64, String)> {
+        let (payload, signature) = token.rsplit_once('.')?;
+        self.mac(payload).verify_slice(&hex::decode(signature).ok()?).ok()?;
+
+        let mut parts = payload.splitn(3, '.');
+        let user_id = parts.next()?.parse().ok()?;
+        let expires_at: i64 = parts.next()?.parse().ok()?;
+        let email = String::from_utf8(hex::decode(parts.next()?).ok()?).ok()?;
+
+        (expires_at > now()).then_some((user_id, email))
+    }
+
+    fn mac(&self, payload: &str) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.secret).expect("HMAC accepts keys of any length");
+        mac.update(b"verify-email.");
+        mac.update(payload.as_bytes());
+        mac
+    }
+}
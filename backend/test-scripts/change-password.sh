@@ -0,0 +1,9 @@
+#!/bin/env sh
+if [ "$1" != "" ] && [ "$2" != "" ]; then
+	curl -X POST 0.0.0.0:3000/users/me/password \
+		-H "Content-Type: application/json" \
+		-b cookies.txt \
+		-d "{ \"current_password\": \"$1\", \"new_password\": \"$2\" }"
+else
+	echo "Usage: $0 <current password> <new password>"
+fi
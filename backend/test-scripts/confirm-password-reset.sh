@@ -0,0 +1,8 @@
+#!/bin/env sh
+if [ "$1" != "" ] && [ "$2" != "" ]; then
+	curl -X POST 0.0.0.0:3000/auth/password-reset/confirm \
+		-H "Content-Type: application/json" \
+		-d "{ \"token\": \"$1\", \"password\": \"$2\" }"
+else
+	echo "Usage: $0 <token> <new password>"
+fi
@@ -0,0 +1,9 @@
+#!/bin/env sh
+if [ "$1" != "" ]; then
+	curl -X POST 0.0.0.0:3000/users/me/totp/confirm \
+		-H "Content-Type: application/json" \
+		-b cookies.txt \
+		-d "{ \"code\": \"$1\" }"
+else
+	echo "Usage: $0 <code>"
+fi
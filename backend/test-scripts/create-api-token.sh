@@ -0,0 +1,11 @@
+#!/bin/env sh
+curl -X POST 0.0.0.0:3000/users/me/tokens \
+	-H "Content-Type: application/json" \
+	-b cookies.txt \
+	-d '
+{
+	"name": "Test script",
+	"scope": "Read",
+	"expires_in": 86400
+}
+'
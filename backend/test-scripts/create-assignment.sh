@@ -0,0 +1,16 @@
+#!/bin/env sh
+if [ "$1" != "" ]; then
+	curl -X POST 0.0.0.0:3000/rooms/$1/assignments \
+		-H "Content-Type: application/json" \
+		-b cookies.txt \
+		-d '
+{
+	"title": "Assignment title",
+	"instructions": "Assignment instructions",
+	"due_date": 1893456000,
+	"max_points": 100
+}
+'
+else
+	echo "Usage: $0 <room id>"
+fi
@@ -0,0 +1,8 @@
+#!/bin/env sh
+if [ "$1" != "" ] && [ "$2" != "" ]; then
+	curl -X POST 0.0.0.0:3000/assignments/$1/submissions \
+		-b cookies.txt \
+		-F "file=@$2;type=${3:-text/plain}"
+else
+	echo "Usage: $0 <assignment id> <file> [content type]"
+fi
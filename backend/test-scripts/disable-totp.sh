@@ -0,0 +1,9 @@
+#!/bin/env sh
+if [ "$1" != "" ]; then
+	curl -X DELETE 0.0.0.0:3000/users/me/totp \
+		-H "Content-Type: application/json" \
+		-b cookies.txt \
+		-d "{ \"password\": \"$1\" }"
+else
+	echo "Usage: $0 <password>"
+fi
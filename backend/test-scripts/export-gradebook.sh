@@ -0,0 +1,7 @@
+#!/bin/env sh
+if [ "$1" != "" ]; then
+	curl -X GET "0.0.0.0:3000/rooms/$1/gradebook?format=${2:-csv}" \
+		-b cookies.txt
+else
+	echo "Usage: $0 <room id> [csv|json]"
+fi
@@ -0,0 +1,3 @@
+#!/bin/env sh
+curl -X GET 0.0.0.0:3000/users/me/tokens \
+	-b cookies.txt
@@ -0,0 +1,8 @@
+#!/bin/env sh
+if [ "$1" != "" ]; then
+	curl -X GET 0.0.0.0:3000/submissions/$1/grade \
+		-H "Content-Type: application/json" \
+		-b cookies.txt
+else
+	echo "Usage: $0 <submission id>"
+fi
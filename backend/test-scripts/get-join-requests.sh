@@ -0,0 +1,7 @@
+#!/bin/env sh
+if [ "$1" != "" ]; then
+	curl 0.0.0.0:3000/rooms/$1/join-requests \
+		-b cookies.txt
+else
+	echo "Usage: $0 <room id>"
+fi
@@ -0,0 +1,7 @@
+#!/bin/env sh
+if [ "$1" != "" ]; then
+	curl -X GET 0.0.0.0:3000/rooms/get \
+		-H "Authorization: Bearer $1"
+else
+	echo "Usage: $0 <API token>"
+fi
@@ -0,0 +1,3 @@
+#!/bin/env sh
+curl 0.0.0.0:3000/auth/sessions \
+	-b cookies.txt
@@ -0,0 +1,8 @@
+#!/bin/env sh
+if [ "$1" != "" ] && [ "$2" != "" ]; then
+	curl -X GET 0.0.0.0:3000/submissions/$1/files/$2 \
+		-b cookies.txt \
+		-OJ
+else
+	echo "Usage: $0 <submission id> <file id>"
+fi
@@ -0,0 +1,4 @@
+#!/bin/env sh
+curl -X GET 0.0.0.0:3000/rooms/trash \
+	-H "Content-Type: application/json" \
+	-b cookies.txt
@@ -0,0 +1,15 @@
+#!/bin/env sh
+if [ "$1" != "" ]; then
+	curl -X PUT 0.0.0.0:3000/submissions/$1/grade \
+		-H "Content-Type: application/json" \
+		-b cookies.txt \
+		-d '
+{
+	"points": 8,
+	"feedback": "Good work",
+	"state": "Returned"
+}
+'
+else
+	echo "Usage: $0 <submission id>"
+fi
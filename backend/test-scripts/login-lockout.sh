@@ -0,0 +1,13 @@
+#!/bin/env sh
+# Wrong passwords until the account locks, the last attempts answer 429 with Retry-After
+for i in 1 2 3 4 5 6; do
+	curl -i -X POST 0.0.0.0:3000/auth/login \
+		-H "Content-Type: application/json" \
+		-d '
+{
+	"password": "NOT-THE-TEST-USER-PASSWORD",
+	"email": "email@example.com"
+}
+'
+	echo
+done
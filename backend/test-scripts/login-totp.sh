@@ -0,0 +1,9 @@
+#!/bin/env sh
+if [ "$1" != "" ] && [ "$2" != "" ]; then
+	curl -X POST 0.0.0.0:3000/auth/login/totp \
+		-c cookies.txt \
+		-H "Content-Type: application/json" \
+		-d "{ \"challenge\": \"$1\", \"code\": \"$2\" }"
+else
+	echo "Usage: $0 <challenge> <code or recovery code>"
+fi
@@ -0,0 +1,12 @@
+#!/bin/env sh
+# Runs a single sign-on login without a browser, against a provider that logs users in
+# without asking, e.g. a local mock IdP:
+#   docker run -p 8080:8080 ghcr.io/navikt/mock-oauth2-server
+#   OIDC_ISSUER_URL=http://localhost:8080/default OIDC_CLIENT_ID=crate cargo run -- --dev
+provider=$(curl -s -o /dev/null -w '%{redirect_url}' \
+	-b cookies.txt -c cookies.txt \
+	0.0.0.0:3000/auth/oidc/login)
+
+callback=$(curl -s -o /dev/null -w '%{redirect_url}' "$provider")
+
+curl -i -b cookies.txt -c cookies.txt "$callback"
@@ -0,0 +1,7 @@
+#!/bin/env sh
+if [ "$1" != "" ] && [ "$2" != "" ]; then
+	curl -X POST 0.0.0.0:3000/rooms/$1/join-requests/$2/reject \
+		-b cookies.txt
+else
+	echo "Usage: $0 <room id> <user id>"
+fi
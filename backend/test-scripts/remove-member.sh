@@ -0,0 +1,8 @@
+#!/bin/env sh
+if [ "$1" != "" ] && [ "$2" != "" ]; then
+	curl -X DELETE "0.0.0.0:3000/rooms/$1/members/$2?ban=${3:-false}" \
+		-H "Content-Type: application/json" \
+		-b cookies.txt
+else
+	echo "Usage: $0 <room id> <user id> [ban: true|false]"
+fi
@@ -0,0 +1,8 @@
+#!/bin/env sh
+if [ "$1" != "" ]; then
+	curl -X POST 0.0.0.0:3000/auth/verify-email/resend \
+		-H "Content-Type: application/json" \
+		-d "{ \"email\": \"$1\" }"
+else
+	echo "Usage: $0 <email>"
+fi
@@ -0,0 +1,7 @@
+#!/bin/env sh
+if [ "$1" != "" ]; then
+	curl -X DELETE 0.0.0.0:3000/users/me/tokens/$1 \
+		-b cookies.txt
+else
+	echo "Usage: $0 <token id>"
+fi
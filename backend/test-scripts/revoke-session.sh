@@ -0,0 +1,7 @@
+#!/bin/env sh
+if [ "$1" != "" ]; then
+	curl -X DELETE 0.0.0.0:3000/auth/sessions/$1 \
+		-b cookies.txt
+else
+	echo "Usage: $0 <session id>"
+fi
@@ -0,0 +1,14 @@
+#!/bin/env sh
+if [ "$1" != "" ]; then
+	curl -X POST 0.0.0.0:3000/rooms/$1/invitation-code/rotate \
+		-H "Content-Type: application/json" \
+		-b cookies.txt \
+		-d '
+{
+	"expires_in": 604800,
+	"max_uses": 30
+}
+'
+else
+	echo "Usage: $0 <room id>"
+fi
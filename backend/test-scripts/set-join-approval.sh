@@ -0,0 +1,9 @@
+#!/bin/env sh
+if [ "$1" != "" ] && [ "$2" != "" ]; then
+	curl -X PUT 0.0.0.0:3000/rooms/$1/join-approval \
+		-H "Content-Type: application/json" \
+		-b cookies.txt \
+		-d "{ \"required\": $2 }"
+else
+	echo "Usage: $0 <room id> <true|false>"
+fi
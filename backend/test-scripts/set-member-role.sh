@@ -0,0 +1,9 @@
+#!/bin/env sh
+if [ "$1" != "" ] && [ "$2" != "" ] && [ "$3" != "" ]; then
+	curl -X PUT 0.0.0.0:3000/rooms/$1/members/$2/role \
+		-H "Content-Type: application/json" \
+		-b cookies.txt \
+		-d "{ \"role\": \"$3\" }"
+else
+	echo "Usage: $0 <room id> <user id> <Teacher|Student>"
+fi
@@ -0,0 +1,3 @@
+#!/bin/env sh
+curl -X POST 0.0.0.0:3000/users/me/totp \
+	-b cookies.txt
@@ -0,0 +1,9 @@
+#!/bin/env sh
+if [ "$1" != "" ] && [ "$2" != "" ]; then
+	curl -X POST 0.0.0.0:3000/rooms/$1/transfer \
+		-H "Content-Type: application/json" \
+		-b cookies.txt \
+		-d "{ \"user_id\": $2 }"
+else
+	echo "Usage: $0 <room id> <new owner user id>"
+fi
@@ -0,0 +1,9 @@
+#!/bin/env sh
+if [ "$1" != "" ] && [ "$2" != "" ] && [ "$3" != "" ]; then
+	curl -X PATCH 0.0.0.0:3000/users/me \
+		-H "Content-Type: application/json" \
+		-b cookies.txt \
+		-d "{ \"name\": \"$1\", \"surname\": \"$2\", \"email\": \"$3\", \"current_password\": \"$4\" }"
+else
+	echo "Usage: $0 <name> <surname> <email> [current password, needed to change the email]"
+fi
@@ -0,0 +1,9 @@
+#!/bin/env sh
+if [ "$1" != "" ] && [ "$2" != "" ] && [ "$3" != "" ]; then
+	curl -X PATCH 0.0.0.0:3000/rooms/$1 \
+		-H "Content-Type: application/json" \
+		-b cookies.txt \
+		-d "{ \"name\": \"$2\", \"color\": \"$3\" }"
+else
+	echo "Usage: $0 <room id> <name> <#rrggbb>"
+fi
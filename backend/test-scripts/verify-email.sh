@@ -0,0 +1,6 @@
+#!/bin/env sh
+if [ "$1" != "" ]; then
+	curl 0.0.0.0:3000/auth/verify-email/$1
+else
+	echo "Usage: $0 <token>"
+fi